@@ -0,0 +1,210 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use syn::{Data, DeriveInput, Expr, Ident, LitInt, LitStr};
+
+
+// Per-variant metadata from `#[kind(...)]`; anything left out falls back to the same
+// defaults as the corresponding epitaph traits.
+struct Variant
+{
+    ident        : Ident,
+    desc         : Option<LitStr>,
+    code         : Option<LitStr>,
+    status       : Option<LitInt>,
+    exit         : Option<LitInt>,
+    severity     : Option<Ident>,
+    grpc         : Option<Ident>,
+    json_rpc     : Option<Expr>,
+    transient    : bool,
+    client_fault : bool,
+    retryable    : Option<bool>,
+}
+
+
+#[proc_macro_derive(ErrorKind, attributes(kind))]
+pub fn derive_error_kind (input : TokenStream) -> TokenStream
+{
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e)     => e.to_compile_error().into(),
+    }
+}
+
+
+fn expand (input : &DeriveInput) -> syn::Result<Tokens>
+{
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _                    => return Err(syn::Error::new_spanned(&input.ident, "ErrorKind can only be derived for enums")),
+    };
+
+    let mut variants = Vec::new();
+    for v in &data.variants {
+        variants.push(parse_variant(v)?);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let pats : Vec<Tokens> = variants.iter().map(|v| { let ident = &v.ident; quote!(#name::#ident{..}) }).collect();
+
+    let descs = variants.iter().map(|v| match v.desc {
+        Some(ref desc) => quote!(#desc),
+        None           => { let ident = v.ident.to_string(); quote!(#ident) },
+    });
+    let codes = variants.iter().map(|v| match v.code {
+        Some(ref code) => quote!(::std::option::Option::Some(#code)),
+        None           => quote!(::std::option::Option::None),
+    });
+    let statuses = variants.iter().map(|v| match v.status {
+        Some(ref status) => quote!(#status),
+        None             => quote!(500),
+    });
+    let exits = variants.iter().map(|v| match v.exit {
+        Some(ref exit) => quote!(#exit),
+        None           => quote!(::epitaph::kind::sysexits::SOFTWARE),
+    });
+    let grpcs = variants.iter().map(|v| match v.grpc {
+        Some(ref grpc) => quote!(::epitaph::kind::GrpcCode::#grpc),
+        None           => quote!(::epitaph::kind::GrpcCode::from_http_status(::epitaph::kind::HttpStatus::http_status(self))),
+    });
+    let json_rpcs = variants.iter().map(|v| match v.json_rpc {
+        Some(ref code) => quote!(#code),
+        None           => quote!(-32000),
+    });
+    let transients = variants.iter().map(|v| v.transient);
+    let client_faults = variants.iter().map(|v| v.client_fault);
+    let retryables = variants.iter().map(|v| v.retryable.unwrap_or(v.transient));
+    let severities = variants.iter().map(|v| match v.severity {
+        Some(ref severity) => quote!(::epitaph::kind::Severity::#severity),
+        None if v.client_fault => quote!(::epitaph::kind::Severity::Low),
+        None if v.transient    => quote!(::epitaph::kind::Severity::Medium),
+        None                   => quote!(::epitaph::kind::Severity::High),
+    });
+
+    Ok(quote!{
+        impl #impl_generics ::epitaph::kind::Describe for #name #ty_generics #where_clause
+        {
+            fn describe (&self) -> &'static str
+            {
+                match *self { #(#pats => #descs,)* }
+            }
+        }
+        impl #impl_generics ::epitaph::kind::ErrorCode for #name #ty_generics #where_clause
+        {
+            fn code (&self) -> ::std::option::Option<&'static str>
+            {
+                match *self { #(#pats => #codes,)* }
+            }
+        }
+        impl #impl_generics ::epitaph::kind::HttpStatus for #name #ty_generics #where_clause
+        {
+            fn http_status (&self) -> u16
+            {
+                match *self { #(#pats => #statuses,)* }
+            }
+        }
+        impl #impl_generics ::epitaph::kind::ExitStatus for #name #ty_generics #where_clause
+        {
+            fn exit_code (&self) -> u8
+            {
+                match *self { #(#pats => #exits,)* }
+            }
+        }
+        impl #impl_generics ::epitaph::kind::RpcKind for #name #ty_generics #where_clause
+        {
+            fn grpc_code (&self) -> ::epitaph::kind::GrpcCode
+            {
+                match *self { #(#pats => #grpcs,)* }
+            }
+            fn json_rpc_code (&self) -> i64
+            {
+                match *self { #(#pats => #json_rpcs,)* }
+            }
+        }
+        impl #impl_generics ::epitaph::kind::Classify for #name #ty_generics #where_clause
+        {
+            fn is_transient (&self) -> bool
+            {
+                match *self { #(#pats => #transients,)* }
+            }
+            fn is_client_fault (&self) -> bool
+            {
+                match *self { #(#pats => #client_faults,)* }
+            }
+            fn is_retryable (&self) -> bool
+            {
+                match *self { #(#pats => #retryables,)* }
+            }
+            fn severity (&self) -> ::epitaph::kind::Severity
+            {
+                match *self { #(#pats => #severities,)* }
+            }
+        }
+    })
+}
+
+
+fn parse_variant (variant : &syn::Variant) -> syn::Result<Variant>
+{
+    let mut parsed = Variant{
+        ident:        variant.ident.clone(),
+        desc:         None,
+        code:         None,
+        status:       None,
+        exit:         None,
+        severity:     None,
+        grpc:         None,
+        json_rpc:     None,
+        transient:    false,
+        client_fault: false,
+        retryable:    None,
+    };
+
+    for attr in variant.attrs.iter().filter(|a| a.path().is_ident("kind")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("desc") {
+                parsed.desc = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("code") {
+                parsed.code = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("status") {
+                parsed.status = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("exit") {
+                parsed.exit = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("severity") {
+                let severity : LitStr = meta.value()?.parse()?;
+                let ident = match &severity.value()[..] {
+                    "low"      => "Low",
+                    "medium"   => "Medium",
+                    "high"     => "High",
+                    "critical" => "Critical",
+                    _          => return Err(meta.error("severity must be one of \"low\", \"medium\", \"high\" or \"critical\"")),
+                };
+                parsed.severity = Some(Ident::new(ident, severity.span()));
+            } else if meta.path.is_ident("grpc") {
+                let grpc : LitStr = meta.value()?.parse()?;
+                parsed.grpc = Some(grpc.parse()?);
+            } else if meta.path.is_ident("json_rpc") {
+                parsed.json_rpc = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("transient") {
+                parsed.transient = true;
+            } else if meta.path.is_ident("client_fault") {
+                parsed.client_fault = true;
+            } else if meta.path.is_ident("retryable") {
+                parsed.retryable = Some(true);
+            } else if meta.path.is_ident("not_retryable") {
+                parsed.retryable = Some(false);
+            } else {
+                return Err(meta.error("unknown kind attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(parsed)
+}
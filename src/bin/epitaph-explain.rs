@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::env;
+use std::fs;
+
+use epitaph::kind::{sysexits, ExitStatus};
+use epitaph::Report;
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Usage,
+    Read,
+    UnknownCode,
+}
+impl ExitStatus for ErrorKind
+{
+    fn exit_code (&self) -> u8
+    {
+        match *self {
+            ErrorKind::Usage       => sysexits::USAGE,
+            ErrorKind::Read        => sysexits::NOINPUT,
+            ErrorKind::UnknownCode => sysexits::DATAERR,
+        }
+    }
+}
+
+error_items!{Error<Kind = ErrorKind> "epitaph-explain"}
+
+
+fn run () -> Result<(), Error>
+{
+    let args : Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() || args.len() > 2 {
+        err!(ErrorKind::Usage; "usage: epitaph-explain <binary> [<code>]");
+    }
+
+    let bytes = attempt_err!(fs::read(&args[0]) => |_| ErrorKind::Read; "could not read {}", args[0]);
+    let entries = epitaph::explain::scan(&bytes);
+    match args.get(1) {
+        Some(code) => match entries.iter().find(|e| e.0 == *code) {
+            Some((_, text)) => println!("{}", text),
+            None            => err!(ErrorKind::UnknownCode; "no explanation for {} in {}", code, args[0]),
+        },
+        None => for (code, text) in &entries {
+            println!("{}: {}", code, text.lines().next().unwrap_or(""));
+        },
+    }
+    Ok(())
+}
+
+
+fn main () -> Result<(), Report<Error>>
+{
+    run()?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use DetailedError;
+
+
+#[doc(hidden)]
+pub const START : &str = "\u{1}epitaph:explain\u{1}";
+#[doc(hidden)]
+pub const END : &str = "\u{1}epitaph:end\u{1}";
+
+
+// Besides the registry itself, each entry is embedded as a marked string so that
+// `epitaph-explain` can find the explanations in a compiled binary without running it.
+#[macro_export]
+macro_rules! explanations
+{
+    (
+        $vis:vis static $name:ident; $($code:expr => $text:expr),* $(,)?
+    ) => (
+        $vis static $name : $crate::explain::Registry = $crate::explain::Registry::new(&[$(($code, $text)),*]);
+        const _ : () = {
+            #[used]
+            static EMBEDDED : &'static [&'static str] = &[$(concat!("\u{1}epitaph:explain\u{1}", $code, "\u{1}", $text, "\u{1}epitaph:end\u{1}")),*];
+        };
+    );
+}
+
+
+pub struct Registry
+{
+    entries : &'static [(&'static str, &'static str)],
+}
+impl Registry
+{
+    #[inline]
+    pub const fn new (entries : &'static [(&'static str, &'static str)]) -> Registry { Registry{entries} }
+
+
+    pub fn get (&self, code : &str) -> Option<&'static str>
+    {
+        self.entries.iter().find(|e| e.0 == code).map(|e| e.1)
+    }
+
+
+    pub fn explain <E> (&self, error : &E) -> Option<&'static str>
+        where E : DetailedError
+    {
+        error.code().and_then(|code| self.get(code))
+    }
+
+
+    #[inline]
+    pub fn entries (&self) -> &'static [(&'static str, &'static str)] { self.entries }
+}
+
+
+// Finds the entries embedded by `explanations!` in the bytes of a compiled binary.
+pub fn scan (bytes : &[u8]) -> Vec<(String, String)>
+{
+    let mut found : Vec<(String, String)> = Vec::new();
+    let mut rest = bytes;
+    while let Some(at) = find(rest, START.as_bytes()) {
+        rest = &rest[at + START.len()..];
+        if let Some(entry) = parse_entry(rest) {
+            if !found.iter().any(|f| f.0 == entry.0) {
+                found.push(entry);
+            }
+        }
+    }
+    found
+}
+
+
+fn parse_entry (bytes : &[u8]) -> Option<(String, String)>
+{
+    // the scanner's own copy of the marker is also in the binary, followed by whatever the
+    // linker put next, so only accept entries with a plausible code and a proper end marker
+    let sep = bytes.iter().take(64).position(|&b| b == 1)?;
+    let code = &bytes[..sep];
+    if code.is_empty() || !code.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-') {
+        return None;
+    }
+
+    let text = &bytes[sep + 1..];
+    let end = find(text, END.as_bytes())?;
+    match (String::from_utf8(code.to_vec()), String::from_utf8(text[..end].to_vec())) {
+        (Ok(code), Ok(text)) => if text.contains('\u{1}') { None } else { Some((code, text)) },
+        _                    => None,
+    }
+}
+
+
+fn find (haystack : &[u8], needle : &[u8]) -> Option<usize>
+{
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
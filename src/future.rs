@@ -0,0 +1,69 @@
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use DetailedFromError;
+use scope::Scoped;
+
+
+pub trait FutureExt : Future + Sized
+{
+    #[track_caller]
+    fn traced (self) -> Traced<Self>
+    {
+        Traced::new(self, None, Location::caller())
+    }
+
+
+    #[track_caller]
+    fn context <S> (self, msg : S) -> Traced<Self>
+        where S : Into<String>
+    {
+        Traced::new(self, Some(msg.into()), Location::caller())
+    }
+
+
+    #[inline]
+    fn in_scope <S> (self, desc : S) -> Scoped<Self>
+        where S : Into<String>
+    {
+        Scoped::new(self, desc.into())
+    }
+}
+impl <F> FutureExt for F where F : Future {}
+
+
+pub struct Traced <F>
+{
+    future : F,
+    msg    : Option<String>,
+    loc    : &'static Location<'static>,
+}
+impl <F> Traced<F>
+{
+    #[inline]
+    fn new (future : F, msg : Option<String>, loc : &'static Location<'static>) -> Traced<F>
+    {
+        Traced{future, msg, loc}
+    }
+}
+impl <F, T, E> Future for Traced<F>
+    where F : Future<Output = Result<T, E>>, E : DetailedFromError<E>
+{
+    type Output = Result<T, E>;
+
+
+    fn poll (self : Pin<&mut Self>, cx : &mut Context) -> Poll<Result<T, E>>
+    {
+        // `future` is structurally pinned and never moved out; `msg` and `loc` are not pinned
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        match future.poll(cx) {
+            Poll::Pending         => Poll::Pending,
+            Poll::Ready(Ok(val))  => Poll::Ready(Ok(val)),
+            Poll::Ready(Err(e))   => Poll::Ready(Err(E::from_error(e, this.msg.take(), this.loc.file(), this.loc.line()))),
+        }
+    }
+}
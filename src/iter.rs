@@ -0,0 +1,125 @@
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::panic::Location;
+
+use DetailedFromError;
+
+
+// what `try_map_traced` uses for a description it never calls
+pub type NoDescription <I> = fn(&I) -> String;
+
+
+pub trait TracedIterator : Iterator + Sized
+{
+    #[track_caller]
+    fn try_map_traced <F, T, E, D> (self, f : F) -> TryMapTraced<Self, F, NoDescription<<Self as Iterator>::Item>, D>
+        where F : FnMut(<Self as Iterator>::Item) -> Result<T, E>, D : DetailedFromError<E>
+    {
+        TryMapTraced::new(self, f, None, Location::caller())
+    }
+
+
+    #[track_caller]
+    fn try_map_described <F, G, T, E, D> (self, describe : G, f : F) -> TryMapTraced<Self, F, G, D>
+        where F : FnMut(<Self as Iterator>::Item) -> Result<T, E>, G : FnMut(&<Self as Iterator>::Item) -> String, D : DetailedFromError<E>
+    {
+        TryMapTraced::new(self, f, Some(describe), Location::caller())
+    }
+
+
+    #[track_caller]
+    fn collect_traced <C, T, E, D> (self) -> Result<C, D>
+        where Self : Iterator<Item = Result<T, E>>, C : FromIterator<T>, D : DetailedFromError<E>
+    {
+        let loc = Location::caller();
+        let mut failed = None;
+        let collected = self.enumerate().map_while(|(i, r)| match r {
+            Ok(val) => Some(val),
+            Err(e)  => {
+                failed = Some(D::from_error(e, Some(item_msg(i, None)), loc.file(), loc.line()));
+                None
+            },
+        }).collect();
+
+        match failed {
+            Some(e) => Err(e),
+            None    => Ok(collected),
+        }
+    }
+
+
+    #[track_caller]
+    fn collect_all_errors <C, T, E, D> (self) -> Result<C, Vec<D>>
+        where Self : Iterator<Item = Result<T, E>>, C : FromIterator<T>, D : DetailedFromError<E>
+    {
+        let loc = Location::caller();
+        let mut failed = Vec::new();
+        let collected = self.enumerate().filter_map(|(i, r)| match r {
+            Ok(val) => Some(val),
+            Err(e)  => {
+                failed.push(D::from_error(e, Some(item_msg(i, None)), loc.file(), loc.line()));
+                None
+            },
+        }).collect();
+
+        if failed.is_empty() {
+            Ok(collected)
+        } else {
+            Err(failed)
+        }
+    }
+}
+impl <I> TracedIterator for I where I : Iterator {}
+
+
+pub struct TryMapTraced <I, F, G, D>
+{
+    iter     : I,
+    f        : F,
+    describe : Option<G>,
+    index    : usize,
+    loc      : &'static Location<'static>,
+    _dest    : PhantomData<fn() -> D>,
+}
+impl <I, F, G, D> TryMapTraced<I, F, G, D>
+{
+    #[inline]
+    fn new (iter : I, f : F, describe : Option<G>, loc : &'static Location<'static>) -> TryMapTraced<I, F, G, D>
+    {
+        TryMapTraced{iter, f, describe, index: 0, loc, _dest: PhantomData}
+    }
+}
+impl <I, F, G, T, E, D> Iterator for TryMapTraced<I, F, G, D>
+    where I : Iterator, F : FnMut(<I as Iterator>::Item) -> Result<T, E>, G : FnMut(&<I as Iterator>::Item) -> String, D : DetailedFromError<E>
+{
+    type Item = Result<T, D>;
+
+
+    fn next (&mut self) -> Option<Result<T, D>>
+    {
+        let item = self.iter.next()?;
+        let index = self.index;
+        self.index += 1;
+
+        // the item is moved into `f`, so any description has to be taken up front
+        let desc = self.describe.as_mut().map(|describe| describe(&item));
+
+        Some(match (self.f)(item) {
+            Ok(val) => Ok(val),
+            Err(e)  => Err(D::from_error(e, Some(item_msg(index, desc)), self.loc.file(), self.loc.line())),
+        })
+    }
+
+
+    #[inline]
+    fn size_hint (&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+
+fn item_msg (index : usize, desc : Option<String>) -> String
+{
+    match desc {
+        Some(desc) => format!("item {} ({})", index, desc),
+        None       => format!("item {}", index),
+    }
+}
@@ -0,0 +1,712 @@
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::marker::PhantomData;
+use std::num::{ParseIntError, TryFromIntError};
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+use std::sync::{Mutex, RwLock};
+use std::sync::mpsc::RecvError;
+
+use DetailedError;
+
+
+pub mod sysexits
+{
+    pub const OK          : u8 = 0;
+    pub const USAGE       : u8 = 64;
+    pub const DATAERR     : u8 = 65;
+    pub const NOINPUT     : u8 = 66;
+    pub const NOUSER      : u8 = 67;
+    pub const NOHOST      : u8 = 68;
+    pub const UNAVAILABLE : u8 = 69;
+    pub const SOFTWARE    : u8 = 70;
+    pub const OSERR       : u8 = 71;
+    pub const OSFILE      : u8 = 72;
+    pub const CANTCREAT   : u8 = 73;
+    pub const IOERR       : u8 = 74;
+    pub const TEMPFAIL    : u8 = 75;
+    pub const PROTOCOL    : u8 = 76;
+    pub const NOPERM      : u8 = 77;
+    pub const CONFIG      : u8 = 78;
+}
+
+
+pub trait ExitStatus
+{
+    #[inline]
+    fn exit_code (&self) -> u8 { sysexits::SOFTWARE }
+}
+
+
+impl ExitStatus for io::ErrorKind
+{
+    fn exit_code (&self) -> u8
+    {
+        match *self {
+            io::ErrorKind::NotFound          => sysexits::NOINPUT,
+            io::ErrorKind::PermissionDenied  => sysexits::NOPERM,
+            io::ErrorKind::AlreadyExists     => sysexits::CANTCREAT,
+            io::ErrorKind::InvalidInput      => sysexits::USAGE,
+            io::ErrorKind::InvalidData       |
+            io::ErrorKind::UnexpectedEof     => sysexits::DATAERR,
+            io::ErrorKind::ConnectionRefused |
+            io::ErrorKind::ConnectionReset   |
+            io::ErrorKind::ConnectionAborted |
+            io::ErrorKind::NotConnected      |
+            io::ErrorKind::AddrNotAvailable  => sysexits::UNAVAILABLE,
+            io::ErrorKind::TimedOut          |
+            io::ErrorKind::WouldBlock        |
+            io::ErrorKind::Interrupted       => sysexits::TEMPFAIL,
+            _                                => sysexits::IOERR,
+        }
+    }
+}
+
+
+pub trait IoKind
+{
+    #[inline]
+    fn io_kind (&self) -> io::ErrorKind { io::ErrorKind::Other }
+}
+
+
+impl IoKind for io::ErrorKind
+{
+    #[inline]
+    fn io_kind (&self) -> io::ErrorKind { *self }
+}
+
+
+pub trait Describe
+{
+    fn describe (&self) -> &'static str;
+}
+
+
+pub trait HttpStatus
+{
+    #[inline]
+    fn http_status (&self) -> u16 { 500 }
+}
+
+
+impl HttpStatus for io::ErrorKind
+{
+    fn http_status (&self) -> u16
+    {
+        match *self {
+            io::ErrorKind::NotFound         => 404,
+            io::ErrorKind::PermissionDenied => 403,
+            io::ErrorKind::AlreadyExists    => 409,
+            io::ErrorKind::InvalidInput     |
+            io::ErrorKind::InvalidData      => 400,
+            io::ErrorKind::TimedOut         => 504,
+            _                               => 500,
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GrpcCode
+{
+    Ok                 = 0,
+    Cancelled          = 1,
+    Unknown            = 2,
+    InvalidArgument    = 3,
+    DeadlineExceeded   = 4,
+    NotFound           = 5,
+    AlreadyExists      = 6,
+    PermissionDenied   = 7,
+    ResourceExhausted  = 8,
+    FailedPrecondition = 9,
+    Aborted            = 10,
+    OutOfRange         = 11,
+    Unimplemented      = 12,
+    Internal           = 13,
+    Unavailable        = 14,
+    DataLoss           = 15,
+    Unauthenticated    = 16,
+}
+impl GrpcCode
+{
+    pub fn from_i32 (code : i32) -> GrpcCode
+    {
+        match code {
+            0  => GrpcCode::Ok,
+            1  => GrpcCode::Cancelled,
+            3  => GrpcCode::InvalidArgument,
+            4  => GrpcCode::DeadlineExceeded,
+            5  => GrpcCode::NotFound,
+            6  => GrpcCode::AlreadyExists,
+            7  => GrpcCode::PermissionDenied,
+            8  => GrpcCode::ResourceExhausted,
+            9  => GrpcCode::FailedPrecondition,
+            10 => GrpcCode::Aborted,
+            11 => GrpcCode::OutOfRange,
+            12 => GrpcCode::Unimplemented,
+            13 => GrpcCode::Internal,
+            14 => GrpcCode::Unavailable,
+            15 => GrpcCode::DataLoss,
+            16 => GrpcCode::Unauthenticated,
+            _  => GrpcCode::Unknown,
+        }
+    }
+
+
+    // the usual HTTP to gRPC mapping, for kinds that only know their HTTP status
+    pub fn from_http_status (status : u16) -> GrpcCode
+    {
+        match status {
+            200 ..= 299 => GrpcCode::Ok,
+            400         => GrpcCode::InvalidArgument,
+            401         => GrpcCode::Unauthenticated,
+            403         => GrpcCode::PermissionDenied,
+            404         => GrpcCode::NotFound,
+            409         => GrpcCode::Aborted,
+            429         => GrpcCode::ResourceExhausted,
+            499         => GrpcCode::Cancelled,
+            501         => GrpcCode::Unimplemented,
+            503         => GrpcCode::Unavailable,
+            504         => GrpcCode::DeadlineExceeded,
+            402         |
+            405 ..= 408 |
+            410 ..= 428 |
+            430 ..= 498 => GrpcCode::FailedPrecondition,
+            500         |
+            502         |
+            505 ..= 599 => GrpcCode::Internal,
+            _           => GrpcCode::Unknown,
+        }
+    }
+}
+
+
+// The usual mapping back to HTTP, as used by gRPC gateways.
+impl HttpStatus for GrpcCode
+{
+    fn http_status (&self) -> u16
+    {
+        match *self {
+            GrpcCode::Ok                 => 200,
+            GrpcCode::Cancelled          => 499,
+            GrpcCode::InvalidArgument    |
+            GrpcCode::FailedPrecondition |
+            GrpcCode::OutOfRange         => 400,
+            GrpcCode::Unauthenticated    => 401,
+            GrpcCode::PermissionDenied   => 403,
+            GrpcCode::NotFound           => 404,
+            GrpcCode::AlreadyExists      |
+            GrpcCode::Aborted            => 409,
+            GrpcCode::ResourceExhausted  => 429,
+            GrpcCode::Unimplemented      => 501,
+            GrpcCode::Unavailable        => 503,
+            GrpcCode::DeadlineExceeded   => 504,
+            GrpcCode::Unknown            |
+            GrpcCode::Internal           |
+            GrpcCode::DataLoss           => 500,
+        }
+    }
+}
+
+
+pub trait RpcKind : HttpStatus
+{
+    #[inline]
+    fn grpc_code (&self) -> GrpcCode { GrpcCode::from_http_status(self.http_status()) }
+    // -32000 to -32099 are reserved by JSON-RPC 2.0 for implementation-defined server errors
+    #[inline]
+    fn json_rpc_code (&self) -> i64 { -32000 }
+}
+
+
+impl RpcKind for io::ErrorKind {}
+
+
+pub trait ErrorCode
+{
+    fn code (&self) -> Option<&'static str>;
+}
+
+
+// `error_items!` can't require every kind to have a code, so it calls `probe_code` on a
+// `&CodeProbe`: the `HasCode` impl is picked when the kind implements `ErrorCode`, and
+// method resolution only falls back to the autoref'd `NoCode` impl when it doesn't.
+#[doc(hidden)]
+pub struct CodeProbe <'a, K : 'a> (pub &'a K);
+#[doc(hidden)]
+pub trait HasCode
+{
+    fn probe_code (&self) -> Option<&'static str>;
+}
+impl <'a, K> HasCode for CodeProbe<'a, K>
+    where K : ErrorCode
+{
+    #[inline]
+    fn probe_code (&self) -> Option<&'static str> { self.0.code() }
+}
+#[doc(hidden)]
+pub trait NoCode
+{
+    #[inline]
+    fn probe_code (&self) -> Option<&'static str> { None }
+}
+impl <'a, 'b, K> NoCode for &'b CodeProbe<'a, K> {}
+
+
+// Implemented once on a kind so that `attempt_err!` and `impl_from_error!` can pick the
+// kind for common std errors without a closure at every call site.
+pub trait InferKind : Sized
+{
+    fn unknown () -> Self;
+
+
+    #[inline]
+    fn from_io_kind (_ : io::ErrorKind) -> Self { Self::unknown() }
+    #[inline]
+    fn from_parse_int (_ : &ParseIntError) -> Self { Self::unknown() }
+    #[inline]
+    fn from_utf8 (_ : &Utf8Error) -> Self { Self::unknown() }
+    #[inline]
+    fn from_try_from_int (_ : &TryFromIntError) -> Self { Self::unknown() }
+    #[inline]
+    fn from_recv (_ : &RecvError) -> Self { Self::unknown() }
+}
+
+
+pub trait KindCause
+{
+    fn infer_kind <K> (&self) -> K where K : InferKind;
+}
+
+
+impl KindCause for io::Error
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_io_kind(self.kind()) }
+}
+impl KindCause for ParseIntError
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_parse_int(self) }
+}
+impl KindCause for Utf8Error
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_utf8(self) }
+}
+impl KindCause for FromUtf8Error
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_utf8(&self.utf8_error()) }
+}
+impl KindCause for TryFromIntError
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_try_from_int(self) }
+}
+impl KindCause for RecvError
+{
+    #[inline]
+    fn infer_kind <K> (&self) -> K where K : InferKind { K::from_recv(self) }
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity
+{
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level
+{
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+
+pub trait Classify
+{
+    #[inline]
+    fn is_transient (&self) -> bool { false }
+    #[inline]
+    fn is_client_fault (&self) -> bool { false }
+    #[inline]
+    fn is_retryable (&self) -> bool { self.is_transient() }
+
+
+    fn severity (&self) -> Severity
+    {
+        if self.is_client_fault() {
+            Severity::Low
+        } else if self.is_transient() {
+            Severity::Medium
+        } else {
+            Severity::High
+        }
+    }
+
+
+    fn log_level (&self) -> Level
+    {
+        match self.severity() {
+            Severity::Low      => Level::Info,
+            Severity::Medium   => Level::Warn,
+            Severity::High     |
+            Severity::Critical => Level::Error,
+        }
+    }
+}
+
+
+impl Classify for io::ErrorKind
+{
+    fn is_transient (&self) -> bool
+    {
+        matches!(*self,
+            io::ErrorKind::Interrupted       |
+            io::ErrorKind::WouldBlock        |
+            io::ErrorKind::TimedOut          |
+            io::ErrorKind::ConnectionRefused |
+            io::ErrorKind::ConnectionReset   |
+            io::ErrorKind::ConnectionAborted
+        )
+    }
+
+
+    fn is_client_fault (&self) -> bool
+    {
+        matches!(*self,
+            io::ErrorKind::NotFound         |
+            io::ErrorKind::PermissionDenied |
+            io::ErrorKind::AlreadyExists    |
+            io::ErrorKind::InvalidInput     |
+            io::ErrorKind::InvalidData
+        )
+    }
+}
+
+
+pub fn chain <E> (error : &E) -> Chain<'_, E>
+    where E : DetailedError + 'static
+{
+    Chain{next: Some(error as &(dyn Error + 'static)), _dest: ::std::marker::PhantomData}
+}
+
+
+pub struct Chain <'a, E>
+{
+    next  : Option<&'a (dyn Error + 'static)>,
+    _dest : ::std::marker::PhantomData<&'a E>,
+}
+impl <'a, E> Iterator for Chain<'a, E>
+    where E : DetailedError + 'static
+{
+    type Item = &'a E;
+
+
+    fn next (&mut self) -> Option<&'a E>
+    {
+        while let Some(error) = self.next {
+            self.next = error.source();
+            if let Some(e) = error.downcast_ref::<E>() {
+                return Some(e);
+            }
+        }
+        None
+    }
+}
+
+
+static CLASSIFIERS : RwLock<Vec<(TypeId, Classifier)>> = RwLock::new(Vec::new());
+
+
+type Classifier = for<'a> fn(&'a (dyn Error + 'static)) -> Option<&'a dyn Classify>;
+
+
+// Every kind in the cause chain that can be classified: `E`'s own, and those of the other
+// error types below it that have registered a classifier.
+fn classes <E> (error : &E) -> Vec<&dyn Classify>
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    let classifiers = match CLASSIFIERS.read() {
+        Ok(classifiers) => classifiers,
+        Err(e)          => e.into_inner(),
+    };
+    let mut classes = Vec::new();
+    let mut next = Some(error as &(dyn Error + 'static));
+    while let Some(error) = next {
+        next = error.source();
+        match error.downcast_ref::<E>() {
+            Some(e) => classes.push(e.kind() as &dyn Classify),
+            None    => classes.extend(classifiers.iter().filter_map(|c| (c.1)(error)).next()),
+        }
+    }
+    classes
+}
+
+
+pub fn is_transient <E> (error : &E) -> bool
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    classes(error).iter().any(|k| k.is_transient())
+}
+
+
+pub fn is_retryable <E> (error : &E) -> bool
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    classes(error).iter().any(|k| k.is_retryable())
+}
+
+
+pub fn is_client_fault <E> (error : &E) -> bool
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    classes(error).iter().any(|k| k.is_client_fault())
+}
+
+
+pub fn severity <E> (error : &E) -> Severity
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    classes(error).iter().map(|k| k.severity()).max().unwrap_or(Severity::Low)
+}
+
+
+pub fn log_level <E> (error : &E) -> Level
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    classes(error).iter().map(|k| k.log_level()).min().unwrap_or(Level::Error)
+}
+
+
+// Lets the helpers above classify `E` anywhere in a cause chain, not only where its type
+// is known. `error_items!` errors register themselves the first time one is built, so this
+// is only needed for hand-written `DetailedError` types.
+pub fn register <E> ()
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    let mut classifiers = match CLASSIFIERS.write() {
+        Ok(classifiers) => classifiers,
+        Err(e)          => e.into_inner(),
+    };
+    if !classifiers.iter().any(|c| c.0 == TypeId::of::<E>()) {
+        classifiers.push((TypeId::of::<E>(), |e| e.downcast_ref::<E>().map(|e| e.kind() as &dyn Classify)));
+    }
+}
+
+
+// `error_items!` can't require every kind to be `Classify`, so it registers through a
+// `&ClassifyProbe` the same way it finds codes with `CodeProbe`.
+#[doc(hidden)]
+pub struct ClassifyProbe <E> (pub PhantomData<E>);
+#[doc(hidden)]
+pub trait HasClassify
+{
+    fn probe_classify (&self);
+}
+impl <E> HasClassify for ClassifyProbe<E>
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Classify
+{
+    #[inline]
+    fn probe_classify (&self) { register::<E>() }
+}
+#[doc(hidden)]
+pub trait NoClassify
+{
+    #[inline]
+    fn probe_classify (&self) {}
+}
+impl <E> NoClassify for &ClassifyProbe<E> {}
+
+
+static REGISTRY : Mutex<Vec<&'static KindInfo>> = Mutex::new(Vec::new());
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KindInfo
+{
+    pub namespace    : Cow<'static, str>,
+    pub name         : Cow<'static, str>,
+    pub desc         : Option<Cow<'static, str>>,
+    pub code         : Option<Cow<'static, str>>,
+    pub status       : u16,
+    pub exit         : u8,
+    pub transient    : bool,
+    pub client_fault : bool,
+}
+impl KindInfo
+{
+    pub fn new <N, S> (namespace : N, name : S) -> KindInfo
+        where N : Into<Cow<'static, str>>, S : Into<Cow<'static, str>>
+    {
+        KindInfo{
+            namespace:    namespace.into(),
+            name:         name.into(),
+            desc:         None,
+            code:         None,
+            status:       500,
+            exit:         sysexits::SOFTWARE,
+            transient:    false,
+            client_fault: false,
+        }
+    }
+
+
+    pub fn desc <S> (mut self, desc : S) -> KindInfo
+        where S : Into<Cow<'static, str>>
+    {
+        self.desc = Some(desc.into());
+        self
+    }
+
+
+    pub fn code <S> (mut self, code : S) -> KindInfo
+        where S : Into<Cow<'static, str>>
+    {
+        self.code = Some(code.into());
+        self
+    }
+
+
+    pub fn status (mut self, status : u16) -> KindInfo
+    {
+        self.status = status;
+        self
+    }
+
+
+    pub fn exit (mut self, exit : u8) -> KindInfo
+    {
+        self.exit = exit;
+        self
+    }
+
+
+    pub fn transient (mut self) -> KindInfo
+    {
+        self.transient = true;
+        self
+    }
+
+
+    pub fn client_fault (mut self) -> KindInfo
+    {
+        self.client_fault = true;
+        self
+    }
+}
+
+
+// An interned kind for kind sets that aren't known when the error type is defined. Each
+// namespace and name pair is registered once and leaked, so comparing two kinds is a
+// pointer comparison.
+#[derive(Clone, Copy)]
+pub struct OpenKind (&'static KindInfo);
+impl OpenKind
+{
+    // Registering a namespace and name that already exist returns the existing kind and
+    // ignores the new metadata.
+    pub fn register (info : KindInfo) -> OpenKind
+    {
+        let mut registry = match REGISTRY.lock() {
+            Ok(registry) => registry,
+            Err(e)       => e.into_inner(),
+        };
+        if let Some(info) = registry.iter().find(|i| i.namespace == info.namespace && i.name == info.name) {
+            return OpenKind(info);
+        }
+
+        let info : &'static KindInfo = Box::leak(Box::new(info));
+        registry.push(info);
+        OpenKind(info)
+    }
+
+
+    pub fn lookup (namespace : &str, name : &str) -> Option<OpenKind>
+    {
+        registered().into_iter().find(|k| k.namespace() == namespace && k.name() == name)
+    }
+
+
+    #[inline]
+    pub fn info (&self) -> &'static KindInfo { self.0 }
+    #[inline]
+    pub fn namespace (&self) -> &'static str { &self.0.namespace }
+    #[inline]
+    pub fn name (&self) -> &'static str { &self.0.name }
+}
+impl PartialEq for OpenKind
+{
+    #[inline]
+    fn eq (&self, other : &OpenKind) -> bool { ::std::ptr::eq(self.0, other.0) }
+}
+impl Eq for OpenKind {}
+impl Hash for OpenKind
+{
+    #[inline]
+    fn hash <H> (&self, state : &mut H) where H : Hasher { (self.0 as *const KindInfo).hash(state) }
+}
+impl Debug for OpenKind
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> { Display::fmt(self, fmt) }
+}
+impl Display for OpenKind
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> { write!(fmt, "{}::{}", self.0.namespace, self.0.name) }
+}
+impl Describe for OpenKind
+{
+    fn describe (&self) -> &'static str
+    {
+        match self.0.desc {
+            Some(ref desc) => desc,
+            None           => &self.0.name,
+        }
+    }
+}
+impl ErrorCode for OpenKind
+{
+    #[inline]
+    fn code (&self) -> Option<&'static str> { self.0.code.as_deref() }
+}
+impl HttpStatus for OpenKind
+{
+    #[inline]
+    fn http_status (&self) -> u16 { self.0.status }
+}
+impl ExitStatus for OpenKind
+{
+    #[inline]
+    fn exit_code (&self) -> u8 { self.0.exit }
+}
+impl RpcKind for OpenKind {}
+impl Classify for OpenKind
+{
+    #[inline]
+    fn is_transient (&self) -> bool { self.0.transient }
+    #[inline]
+    fn is_client_fault (&self) -> bool { self.0.client_fault }
+}
+
+
+pub fn registered () -> Vec<OpenKind>
+{
+    match REGISTRY.lock() {
+        Ok(registry) => registry.iter().map(|&i| OpenKind(i)).collect(),
+        Err(e)       => e.into_inner().iter().map(|&i| OpenKind(i)).collect(),
+    }
+}
@@ -4,14 +4,69 @@ use std::any::Any;
 use std::borrow::ToOwned;
 use std::fmt::{self, Debug, Display, Formatter};
 
+#[cfg(feature = "derive")]
+extern crate epitaph_derive;
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "eyre")]
+extern crate eyre;
+#[cfg(feature = "alternate-future")]
+extern crate alternate_future;
+#[cfg(feature = "futures-channel")]
+extern crate futures_channel;
+#[cfg(feature = "json")]
+extern crate serde;
+#[cfg(feature = "json")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "json")]
+extern crate serde_json;
+
+#[macro_use]
+pub mod scope;
+pub mod future;
+#[macro_use]
+pub mod explain;
+pub mod iter;
+pub mod kind;
+#[macro_use]
+pub mod panic;
+pub mod report;
+#[macro_use]
+pub mod retry;
+#[cfg(feature = "json")]
+pub mod problem;
+#[cfg(feature = "alternate-future")]
+pub mod promise;
+pub mod thread;
+
+#[cfg(feature = "derive")]
+pub use epitaph_derive::ErrorKind;
+pub use future::FutureExt;
+pub use iter::TracedIterator;
+pub use kind::{Classify, ErrorCode, ExitStatus, InferKind};
+pub use report::{Record, Report};
+
 
 pub trait DetailedError : std::error::Error
 {
     type Kind;
     
-    fn new (kind : <Self as DetailedError>::Kind, cause : Option<Box<std::error::Error + Send + Sync>>, desc : String, file : &'static str, line : u32) -> Self;
+    fn new (kind : <Self as DetailedError>::Kind, cause : Option<Box<dyn std::error::Error + Send + Sync>>, desc : String, file : &'static str, line : u32) -> Self;
     fn kind (&self) -> &<Self as DetailedError>::Kind;
     fn trace (&self) -> &[(&'static str, u32)];
+    #[inline]
+    fn msg (&self) -> &str { "" }
+    #[inline]
+    fn code (&self) -> Option<&'static str> { None }
+    #[inline]
+    fn notes (&self) -> &[(usize, String)] { &[] }
+    #[inline]
+    fn scopes (&self) -> &[String] { &[] }
+    #[inline]
+    fn hops (&self) -> &[thread::Hop] { &[] }
+    #[inline]
+    fn fields (&self) -> &[(String, String)] { &[] }
 }
 
 
@@ -21,6 +76,364 @@ pub trait DetailedFromError <E>
 }
 
 
+impl <E> DetailedFromError<E> for Box<dyn std::error::Error>
+    where E : DetailedError + DetailedFromError<E> + 'static
+{
+    #[inline]
+    fn from_error (error : E, msg : Option<String>, file : &'static str, line : u32) -> Box<dyn std::error::Error>
+    {
+        Box::new(E::from_error(error, msg, file, line))
+    }
+}
+impl <E> DetailedFromError<E> for Box<dyn std::error::Error + Send + Sync>
+    where E : DetailedError + DetailedFromError<E> + Send + Sync + 'static
+{
+    #[inline]
+    fn from_error (error : E, msg : Option<String>, file : &'static str, line : u32) -> Box<dyn std::error::Error + Send + Sync>
+    {
+        Box::new(E::from_error(error, msg, file, line))
+    }
+}
+#[cfg(feature = "anyhow")]
+impl <E> DetailedFromError<E> for anyhow::Error
+    where E : DetailedError + DetailedFromError<E> + Send + Sync + 'static
+{
+    fn from_error (error : E, msg : Option<String>, file : &'static str, line : u32) -> anyhow::Error
+    {
+        let error = E::from_error(error, None, file, line);
+        let context = TraceContext::new(msg, error.trace());
+        anyhow::Error::new(error).context(context)
+    }
+}
+#[cfg(feature = "eyre")]
+impl <E> DetailedFromError<E> for eyre::Report
+    where E : DetailedError + DetailedFromError<E> + Send + Sync + 'static
+{
+    fn from_error (error : E, msg : Option<String>, file : &'static str, line : u32) -> eyre::Report
+    {
+        let error = E::from_error(error, None, file, line);
+        let context = TraceContext::new(msg, error.trace());
+        eyre::Report::new(error).wrap_err(context)
+    }
+}
+
+
+impl <E> DetailedFromError<E> for std::io::Error
+    where E : DetailedError + DetailedFromError<E> + Send + Sync + 'static, <E as DetailedError>::Kind : kind::IoKind
+{
+    #[inline]
+    fn from_error (error : E, msg : Option<String>, file : &'static str, line : u32) -> std::io::Error
+    {
+        into_io_error(E::from_error(error, msg, file, line))
+    }
+}
+
+
+// The error stays the inner error of the `io::Error`, so `from_io_error` can take it back
+// out with its kind and trace intact.
+pub fn into_io_error <E> (error : E) -> std::io::Error
+    where E : DetailedError + Send + Sync + 'static, <E as DetailedError>::Kind : kind::IoKind
+{
+    let kind = kind::IoKind::io_kind(error.kind());
+    std::io::Error::new(kind, error)
+}
+
+
+pub fn from_io_error <E> (error : std::io::Error) -> Result<E, std::io::Error>
+    where E : DetailedError + Send + Sync + 'static
+{
+    if !error.get_ref().is_some_and(|e| e.is::<E>()) {
+        return Err(error);
+    }
+    match error.into_inner().map(|e| e.downcast::<E>()) {
+        Some(Ok(e)) => Ok(*e),
+        _           => unreachable!(),
+    }
+}
+
+
+// Everything an `error_items!` error is made of, so that it can be rebuilt as a different
+// error type without losing its trace.
+pub struct Parts <K>
+{
+    pub kind   : K,
+    pub cause  : Option<Box<dyn std::error::Error + Send + Sync>>,
+    pub msg    : String,
+    pub trace  : Vec<(&'static str, u32)>,
+    pub notes  : Vec<(usize, String)>,
+    pub scopes : Vec<String>,
+    pub hops   : Vec<thread::Hop>,
+    pub fields : Vec<(String, String)>,
+}
+impl <K> Parts<K>
+{
+    pub fn map_kind <F, L> (self, f : F) -> Parts<L>
+        where F : FnOnce(K) -> L
+    {
+        Parts{
+            kind:   f(self.kind),
+            cause:  self.cause,
+            msg:    self.msg,
+            trace:  self.trace,
+            notes:  self.notes,
+            scopes: self.scopes,
+            hops:   self.hops,
+            fields: self.fields,
+        }
+    }
+}
+
+
+// The parts of an `error_items!` error that are usually empty, boxed on first use so
+// that the error stays small enough to return by value.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Extras (Option<Box<ExtraParts>>);
+#[derive(Default)]
+struct ExtraParts
+{
+    notes  : Vec<(usize, String)>,
+    scopes : Vec<String>,
+    hops   : Vec<thread::Hop>,
+    fields : Vec<(String, String)>,
+}
+impl Extras
+{
+    pub fn new (notes : Vec<(usize, String)>, scopes : Vec<String>, hops : Vec<thread::Hop>, fields : Vec<(String, String)>) -> Extras
+    {
+        if notes.is_empty() && scopes.is_empty() && hops.is_empty() && fields.is_empty() {
+            Extras(None)
+        } else {
+            Extras(Some(Box::new(ExtraParts{notes, scopes, hops, fields})))
+        }
+    }
+
+
+    pub fn into_parts <K> (self, kind : K, cause : Option<Box<dyn std::error::Error + Send + Sync>>, msg : String, trace : Vec<(&'static str, u32)>) -> Parts<K>
+    {
+        let e = self.0.map(|e| *e).unwrap_or_default();
+        Parts{kind, cause, msg, trace, notes: e.notes, scopes: e.scopes, hops: e.hops, fields: e.fields}
+    }
+
+
+    pub fn notes (&self) -> &[(usize, String)]
+    {
+        match self.0 {
+            Some(ref e) => &e.notes,
+            None        => &[],
+        }
+    }
+    pub fn scopes (&self) -> &[String]
+    {
+        match self.0 {
+            Some(ref e) => &e.scopes,
+            None        => &[],
+        }
+    }
+    pub fn hops (&self) -> &[thread::Hop]
+    {
+        match self.0 {
+            Some(ref e) => &e.hops,
+            None        => &[],
+        }
+    }
+    pub fn fields (&self) -> &[(String, String)]
+    {
+        match self.0 {
+            Some(ref e) => &e.fields,
+            None        => &[],
+        }
+    }
+
+
+    #[inline]
+    pub fn push_note (&mut self, at : usize, note : String) { self.parts_mut().notes.push((at, note)) }
+    #[inline]
+    pub fn push_hop (&mut self, hop : thread::Hop) { self.parts_mut().hops.push(hop) }
+    #[inline]
+    pub fn last_hop_mut (&mut self) -> Option<&mut thread::Hop> { self.0.as_mut().and_then(|e| e.hops.last_mut()) }
+
+
+    fn parts_mut (&mut self) -> &mut ExtraParts
+    {
+        self.0.get_or_insert_with(Box::default)
+    }
+}
+
+
+pub trait ErrorParts : DetailedError + Sized
+{
+    fn into_parts (self) -> Parts<<Self as DetailedError>::Kind>;
+    fn from_parts (parts : Parts<<Self as DetailedError>::Kind>) -> Self;
+
+
+    // Attaches a named value for machine consumers, such as a problem+json extension
+    // member or the details of an RPC status.
+    fn with_field <N, V> (self, name : N, value : V) -> Self
+        where N : Into<String>, V : Display
+    {
+        let mut parts = self.into_parts();
+        parts.fields.push((name.into(), value.to_string()));
+        Self::from_parts(parts)
+    }
+}
+
+
+pub trait ForeignKind : DetailedError
+{
+    fn foreign_kind () -> <Self as DetailedError>::Kind;
+}
+
+
+impl <E> DetailedFromError<Box<dyn std::error::Error + Send + Sync>> for E
+    where E : ForeignKind
+{
+    fn from_error (error : Box<dyn std::error::Error + Send + Sync>, msg : Option<String>, file : &'static str, line : u32) -> E
+    {
+        let desc = match msg {
+            Some(s) => s,
+            None    => format!("{}", error),
+        };
+        E::new(E::foreign_kind(), Some(error), desc, file, line)
+    }
+}
+#[cfg(feature = "anyhow")]
+impl <E> DetailedFromError<anyhow::Error> for E
+    where E : ForeignKind
+{
+    fn from_error (error : anyhow::Error, msg : Option<String>, file : &'static str, line : u32) -> E
+    {
+        // the cause prints the whole chain, so repeating its message here would show it twice
+        let desc = msg.unwrap_or_else(|| "anyhow error".to_owned());
+        E::new(E::foreign_kind(), Some(Box::new(AnyhowErr(error))), desc, file, line)
+    }
+}
+#[cfg(feature = "eyre")]
+impl <E> DetailedFromError<eyre::Report> for E
+    where E : ForeignKind
+{
+    fn from_error (error : eyre::Report, msg : Option<String>, file : &'static str, line : u32) -> E
+    {
+        // the cause prints the whole chain, so repeating its message here would show it twice
+        let desc = msg.unwrap_or_else(|| "eyre report".to_owned());
+        E::new(E::foreign_kind(), Some(Box::new(EyreErr(error))), desc, file, line)
+    }
+}
+
+
+pub trait ResultSink <T, E>
+{
+    fn send_result (self, result : Result<T, E>);
+    // whether the result is handed on to another thread or callback, which decides if the
+    // `s_*` macros record a hop
+    #[inline]
+    fn crosses_threads (&self) -> bool { false }
+    // what that hop names the sink by, if not the expression the sink was given as
+    #[inline]
+    fn name (&self) -> Option<&'static str> { None }
+}
+
+
+impl <T, E, F> ResultSink<T, E> for F
+    where F : FnOnce(Result<T, E>)
+{
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { self(result) }
+}
+impl <T, E> ResultSink<T, E> for std::sync::mpsc::Sender<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.send(result); }
+}
+impl <T, E> ResultSink<T, E> for &std::sync::mpsc::Sender<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.send(result); }
+}
+impl <T, E> ResultSink<T, E> for std::sync::mpsc::SyncSender<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.send(result); }
+}
+impl <T, E> ResultSink<T, E> for &std::sync::mpsc::SyncSender<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.send(result); }
+}
+#[cfg(feature = "alternate-future")]
+impl <T, E> ResultSink<T, E> for alternate_future::Promise<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>)
+    {
+        match result {
+            Ok(val) => self.ok(val),
+            Err(e)  => self.err(e),
+        }
+    }
+}
+#[cfg(feature = "futures-channel")]
+impl <T, E> ResultSink<T, E> for futures_channel::oneshot::Sender<Result<T, E>>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.send(result); }
+}
+
+
+#[derive(Clone)]
+pub struct TraceContext
+{
+    msg   : Option<String>,
+    trace : Vec<(&'static str, u32)>,
+}
+impl TraceContext
+{
+    #[inline]
+    pub fn new (msg : Option<String>, trace : &[(&'static str, u32)]) -> TraceContext
+    {
+        TraceContext{msg, trace: trace.to_vec()}
+    }
+    
+    #[inline]
+    pub fn msg (&self) -> Option<&str> { self.msg.as_deref() }
+    #[inline]
+    pub fn trace (&self) -> &[(&'static str, u32)] { &self.trace }
+}
+impl Debug for TraceContext
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Display::fmt(self, fmt)
+    }
+}
+impl Display for TraceContext
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        match self.msg {
+            Some(ref msg) => write!(fmt, "{}", msg)?,
+            None          => write!(fmt, "epitaph trace")?,
+        }
+        for &(f, l) in self.trace.iter().rev() {
+            write!(fmt, "\n    @ {}:{}", f, l)?;
+        }
+        
+        Ok(())
+    }
+}
+
+
 #[macro_export]
 macro_rules! error_items
 {
@@ -30,9 +443,10 @@ macro_rules! error_items
         pub struct $err_name
         {
             kind   : $kind_name,
-            cause  : Option<Box<::std::error::Error + Send + Sync>>,
+            cause  : Option<Box<dyn (::std::error::Error) + Send + Sync>>,
             msg    : String,
             trace  : Vec<(&'static str, u32)>,
+            extras : $crate::Extras,
         }
         impl $crate::DetailedError for $err_name
         {
@@ -42,13 +456,15 @@ macro_rules! error_items
             #[inline]
             fn new (
                 kind  : $kind_name,
-                cause : Option<Box<::std::error::Error + Send + Sync>>,
+                cause : Option<Box<dyn (::std::error::Error) + Send + Sync>>,
                 msg   : String,
                 file  : &'static str,
                 line  : u32
             ) -> $err_name
             {
-                $err_name{kind: kind, cause: cause, msg: msg, trace: vec!((file, line))}
+                <$err_name as $crate::report::Recordable>::register_detailed();
+                let extras = $crate::Extras::new(Vec::new(), $crate::scope::snapshot(), Vec::new(), Vec::new());
+                $err_name{kind, cause, msg, trace: vec!((file, line)), extras}
             }
             
             
@@ -56,6 +472,82 @@ macro_rules! error_items
             fn kind (&self) -> &$kind_name { &self.kind }
             #[inline]
             fn trace (&self) -> &[(&'static str, u32)] { &self.trace }
+            #[inline]
+            fn msg (&self) -> &str { &self.msg }
+            #[inline]
+            fn code (&self) -> Option<&'static str>
+            {
+                #[allow(unused_imports)]
+                use $crate::kind::{HasCode, NoCode};
+                
+                (&$crate::kind::CodeProbe(&self.kind)).probe_code()
+            }
+            #[inline]
+            fn notes (&self) -> &[(usize, String)] { self.extras.notes() }
+            #[inline]
+            fn scopes (&self) -> &[String] { self.extras.scopes() }
+            #[inline]
+            fn hops (&self) -> &[$crate::thread::Hop] { self.extras.hops() }
+            #[inline]
+            fn fields (&self) -> &[(String, String)] { self.extras.fields() }
+        }
+        impl $crate::ErrorParts for $err_name
+        {
+            fn into_parts (self) -> $crate::Parts<$kind_name>
+            {
+                self.extras.into_parts(self.kind, self.cause, self.msg, self.trace)
+            }
+            
+            
+            fn from_parts (parts : $crate::Parts<$kind_name>) -> $err_name
+            {
+                <$err_name as $crate::report::Recordable>::register_detailed();
+                let extras = $crate::Extras::new(parts.notes, parts.scopes, parts.hops, parts.fields);
+                $err_name{kind: parts.kind, cause: parts.cause, msg: parts.msg, trace: parts.trace, extras}
+            }
+        }
+        impl $crate::report::Recordable for $err_name
+        {
+            fn record_detailed (&self) -> $crate::report::Record
+            {
+                #[allow(unused_imports)]
+                use $crate::report::{HasDebug, NoDebug};
+                
+                $crate::report::Record::detailed_as(self, (&$crate::report::KindProbe(&self.kind)).probe_kind())
+            }
+            
+            
+            fn register_detailed ()
+            {
+                static REGISTERED : ::std::sync::Once = ::std::sync::Once::new();
+                REGISTERED.call_once(|| {
+                    #[allow(unused_imports)]
+                    use $crate::kind::{HasClassify, NoClassify};
+                    
+                    $crate::report::register_recordable::<$err_name>();
+                    (&$crate::kind::ClassifyProbe::<$err_name>(::std::marker::PhantomData)).probe_classify();
+                });
+            }
+        }
+        impl $crate::thread::RecordHop for $err_name
+        {
+            fn record_hop (mut self, mut hop : $crate::thread::Hop) -> $err_name
+            {
+                hop.at = self.trace.len();
+                self.extras.push_hop(hop);
+                self
+            }
+            
+            
+            fn complete_hop (mut self, to : String) -> $err_name
+            {
+                if let Some(hop) = self.extras.last_hop_mut() {
+                    if hop.to.is_none() {
+                        hop.to = Some(to);
+                    }
+                }
+                self
+            }
         }
         impl ::std::error::Error for $err_name
         {
@@ -64,7 +556,16 @@ macro_rules! error_items
             
             
             #[inline]
-            fn cause (&self) -> Option<&::std::error::Error> {
+            fn cause (&self) -> Option<&dyn (::std::error::Error)> {
+                match self.cause {
+                    Some(ref b) => Some(&**b),
+                    None        => None,
+                }
+            }
+            
+            
+            #[inline]
+            fn source (&self) -> Option<&(dyn (::std::error::Error) + 'static)> {
                 match self.cause {
                     Some(ref b) => Some(&**b),
                     None        => None,
@@ -73,24 +574,16 @@ macro_rules! error_items
         }
         impl $crate::DetailedFromError<$err_name> for $err_name
         {
-            fn from_error (mut e : $err_name, _ : Option<String>, file : &'static str, line : u32) -> $err_name
+            fn from_error (mut e : $err_name, msg : Option<String>, file : &'static str, line : u32) -> $err_name
             {
+                if let Some(msg) = msg {
+                    let at = e.trace.len();
+                    e.extras.push_note(at, msg);
+                }
                 e.trace.push((file, line));
                 e
             }
         }
-        //~ impl $crate::DetailedFromError<$err_name> for Box<::std::error::Error>
-        //~ {
-            //~ fn from_error (mut e : $err_name, _ : Option<String>, file : &'static str, line : u32) -> Box<::std::error::Error>
-            //~ {
-                //~ e.trace.push((file, line));
-                //~ Box::new(e)
-            //~ }
-        //~ }
-        //~ impl ::std::error::FromError<$err_name> for Box<::std::error::Error>
-        //~ {
-            //~ fn from_error (e : $err_name) -> Box<::std::error::Error> { Box::new(e) }
-        //~ }
         impl ::std::fmt::Debug for $err_name
         {
             fn fmt (&self, fmt : &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error>
@@ -106,17 +599,43 @@ macro_rules! error_items
             {
                 use ::std::fmt::Display;
                 
-                try!(writeln!(fmt, "{}: {}", $desc_str, self.msg));
-                for &(f, l) in self.trace.iter().rev() {
-                    try!(writeln!(fmt, "    @ {}:{}", f, l));
+                let options = $crate::report::options();
+                match $crate::DetailedError::code(self) {
+                    Some(code) => options.write_headline(fmt, format_args!("{} [{}]: {}", $desc_str, code, self.msg))?,
+                    None       => options.write_headline(fmt, format_args!("{}: {}", $desc_str, self.msg))?,
+                }
+                if options.verbosity == $crate::report::Verbosity::Brief {
+                    return Ok(());
+                }
+                for (i, &(f, l)) in self.trace.iter().enumerate().rev() {
+                    for hop in self.extras.hops().iter().rev().filter(|h| h.at == i + 1) {
+                        options.write_detail(fmt, format_args!("    ~ {}", hop))?;
+                    }
+                    match self.extras.notes().iter().find(|n| n.0 == i) {
+                        Some(&(_, ref note)) => options.write_detail(fmt, format_args!("    @ {}:{}: {}", f, l, note))?,
+                        None                 => options.write_detail(fmt, format_args!("    @ {}:{}", f, l))?,
+                    }
+                }
+                for scope in self.extras.scopes().iter().rev() {
+                    options.write_detail(fmt, format_args!("    in {}", scope))?;
                 }
                 if let Some(ref cause) = self.cause {
-                    try!(Display::fmt(cause, fmt));
+                    Display::fmt(cause, fmt)?;
                 }
                 
                 Ok(())
             }
         }
+    );
+    (
+        $err_name:ident <Kind = $kind_name:ty, Foreign = $foreign_kind:path> $desc_str:expr
+    ) => (
+        error_items!{$err_name <Kind = $kind_name> $desc_str}
+        impl $crate::ForeignKind for $err_name
+        {
+            #[inline]
+            fn foreign_kind () -> $kind_name { $foreign_kind }
+        }
     )
 }
 
@@ -267,149 +786,55 @@ macro_rules! attempt_err
             },
         }
     );
-}
-
-
-
-
-#[macro_export]
-macro_rules! f_ok
-{
-    (
-        ($func:expr) $expr:expr
-    ) => ({
-        $func(Ok($expr));
-        return;
-    });
-    (
-        ($func:expr; $ret:expr) $expr:expr
-    ) => ({
-        $func(Ok($expr));
-        return $ret;
-    });
-}
-#[macro_export]
-macro_rules! f_err
-{
-    (
-        ($func:expr) $kind:expr; $desc:expr
-    ) => ({
-        $func(Err(new_err!($kind; $desc)));
-        return;
-    });
-    (
-        ($func:expr) $kind:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $func(Err(new_err!($kind; $desc, $($arg)*)));
-        return;
-    });
-    (
-        ($func:expr) $kind:expr, $cause:expr; $desc:expr
-    ) => ({
-        $func(Err(new_err!($kind, $cause; $desc)));
-        return;
-    });
-    (
-        ($func:expr) $kind:expr, $cause:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $func(Err(new_err!($kind, $cause; $desc, $($arg)*)));
-        return;
-    });
-
-    (
-        ($func:expr; $ret:expr) $kind:expr; $desc:expr
-    ) => ({
-        $func(Err(new_err!($kind; $desc)));
-        return $ret;
-    });
-    (
-        ($func:expr; $ret:expr) $kind:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $func(Err(new_err!($kind; $desc, $($arg)*)));
-        return $ret;
-    });
-    (
-        ($func:expr; $ret:expr) $kind:expr, $cause:expr; $desc:expr
-    ) => ({
-        $func(Err(new_err!($kind, $cause; $desc)));
-        return $ret;
-    });
-    (
-        ($func:expr; $ret:expr) $kind:expr, $cause:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $func(Err(new_err!($kind, $cause; $desc, $($arg)*)));
-        return $ret;
-    });
-}
-
-
-#[macro_export]
-macro_rules! f_attempt
-{
-    (
-        ($func:expr) $expr:expr
-    ) => (
-        match $expr {
-            Ok(val) => val,
-            Err(e)  => {
-                $func(Err(from_err!(e)));
-                return;
-            },
-        }
-    );
     (
-        ($func:expr) $expr:expr => $desc:expr
+        $expr:expr; $desc:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => {
-                $func(Err(from_err!(e; $desc)));
-                return;
+            Err(e) => {
+                let kind = $crate::kind::KindCause::infer_kind(&e);
+                err!(kind, e; $desc)
             },
         }
     );
     (
-        ($func:expr) $expr:expr => $desc:expr, $($arg:tt)*
+        $expr:expr; $desc:expr, $($arg:tt)*
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => {
-                $func(Err(from_err!(e; $desc, $($arg)*)));
-                return;
+            Err(e) => {
+                let kind = $crate::kind::KindCause::infer_kind(&e);
+                err!(kind, e; $desc, $($arg)*)
             },
         }
     );
+}
 
+
+
+
+#[macro_export]
+macro_rules! attempt_continue
+{
     (
-        ($func:expr; $ret:expr) $expr:expr
-    ) => (
-        match $expr {
-            Ok(val) => val,
-            Err(e)  => {
-                $func(Err(from_err!(e)));
-                return $ret;
-            },
-        }
-    );
-    (
-        ($func:expr; $ret:expr) $expr:expr => $desc:expr
+        ($sink:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $func(Err(from_err!(e; $desc)));
-                return $ret;
+                $sink(from_err!(e));
+                continue;
             },
         }
     );
     (
-        ($func:expr; $ret:expr) $expr:expr => $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $func(Err(from_err!(e; $desc, $($arg)*)));
-                return $ret;
+                $sink(from_err!(e; $($desc)+));
+                continue;
             },
         }
     );
@@ -417,331 +842,421 @@ macro_rules! f_attempt
 
 
 #[macro_export]
-macro_rules! f_attempt_err
+macro_rules! attempt_break
 {
     (
-        ($func:ident) $expr:expr => $kind_fn:expr; $desc:expr
+        ($sink:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => f_err!(($func) $kind_fn(&e), e; $desc),
+            Err(e)  => {
+                $sink(from_err!(e));
+                break;
+            },
         }
     );
     (
-        ($func:ident) $expr:expr => $kind_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => f_err!(($func) $kind_fn(&e), e; $desc, $($arg)*),
+            Err(e)  => {
+                $sink(from_err!(e; $($desc)+));
+                break;
+            },
         }
     );
+
     (
-        ($func:ident) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr
+        ($sink:expr; $val:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                f_err!(($func) kind, cause; $desc)
+            Err(e)  => {
+                $sink(from_err!(e));
+                break $val;
             },
         }
     );
     (
-        ($func:ident) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr; $val:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                f_err!(($func) kind, cause; $desc, $($arg)*)
+            Err(e)  => {
+                $sink(from_err!(e; $($desc)+));
+                break $val;
             },
         }
     );
+}
 
+
+#[macro_export]
+macro_rules! attempt_or
+{
     (
-        ($func:ident; $ret:expr) $expr:expr => $kind_fn:expr; $desc:expr
+        $expr:expr => $default:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => f_err!(($func; $ret) $kind_fn(&e), e; $desc),
+            Err(_)  => $default,
         }
     );
+}
+
+
+#[macro_export]
+macro_rules! attempt_unless
+{
     (
-        ($func:ident; $ret:expr) $expr:expr => $kind_fn:expr; $desc:expr, $($arg:tt)*
+        $expr:expr; $($($kind:pat)|+ => $fallback:expr),+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => f_err!(($func; $ret) $kind_fn(&e), e; $desc, $($arg)*),
+            Err(e)  => match *$crate::DetailedError::kind(&e) {
+                $($($kind)|+ => $fallback,)+
+                _ => return Err(from_err!(e)),
+            },
         }
     );
+}
+
+
+#[macro_export]
+macro_rules! attempt_any
+{
+    // each alternative is tried in the failure arm of the one before it, so there is no
+    // loop for a `break` or `continue` inside an alternative to bind to
     (
-        ($func:ident; $ret:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr
+        @alts $causes:ident; $alt:expr => $kind:expr; $($desc:tt)+
     ) => (
-        match $expr {
+        match $alt {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                f_err!(($func; $ret) kind, cause; $desc)
+            Err(e)  => {
+                $causes.push(e.into());
+                err!($kind, $crate::MultiErr::new($causes); $($desc)+)
             },
         }
     );
     (
-        ($func:ident; $ret:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr, $($arg:tt)*
+        @alts $causes:ident; $alt:expr, $($rest:expr),+ => $($fail:tt)+
     ) => (
-        match $expr {
+        match $alt {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                f_err!(($func; $ret) kind, cause; $desc, $($arg)*)
+            Err(e)  => {
+                $causes.push(e.into());
+                attempt_any!(@alts $causes; $($rest),+ => $($fail)+)
             },
         }
     );
+    (
+        $($alt:expr),+ => $kind:expr; $($desc:tt)+
+    ) => ({
+        let mut causes : Vec<Box<dyn (::std::error::Error) + Send + Sync>> = Vec::new();
+        attempt_any!(@alts causes; $($alt),+ => $kind; $($desc)+)
+    });
 }
 
 
 
 
-
-
-#[cfg(feature = "alternate-future")]
+#[doc(hidden)]
 #[macro_export]
-macro_rules! p_ok
+macro_rules! s_send_err
 {
     (
-        ($prom:expr) $expr:expr
-    ) => ({
-        $prom.ok($expr);
-        return;
-    });
-    (
-        ($prom:expr; $ret:expr) $expr:expr
+        $sink:expr, $err:expr
     ) => ({
-        $prom.ok($expr);
-        return $ret;
+        let sink = $sink;
+        let err = $crate::thread::sink_err(&sink, $err);
+        let err = match $crate::thread::sink_hop(&sink, stringify!($sink), file!(), line!()) {
+            Some(hop) => {
+                #[allow(unused_imports)]
+                use $crate::thread::{HasHop, NoHop};
+                
+                (&$crate::thread::HopProbe::of(&err)).probe_hop()(err, hop)
+            },
+            None      => err,
+        };
+        $crate::ResultSink::send_result(sink, Err(err));
     });
 }
-#[cfg(feature = "alternate-future")]
+
+
 #[macro_export]
-macro_rules! p_err
+macro_rules! s_ok
 {
     (
-        ($prom:expr) $kind:expr; $desc:expr
-    ) => ({
-        $prom.err(new_err!($kind; $desc));
-        return;
-    });
-    (
-        ($prom:expr) $kind:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr
     ) => ({
-        $prom.err(new_err!($kind; $desc, $($arg)*));
+        $crate::ResultSink::send_result($sink, Ok($expr));
         return;
     });
     (
-        ($prom:expr) $kind:expr, $cause:expr; $desc:expr
+        ($sink:expr; $ret:expr) $expr:expr
     ) => ({
-        $prom.err(new_err!($kind, $cause; $desc));
-        return;
-    });
-    (
-        ($prom:expr) $kind:expr, $cause:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $prom.err(new_err!($kind, $cause; $desc, $($arg)*));
-        return;
-    });
-
-    (
-        ($prom:expr; $ret:expr) $kind:expr; $desc:expr
-    ) => ({
-        $prom.err(new_err!($kind; $desc));
-        return $ret;
-    });
-    (
-        ($prom:expr; $ret:expr) $kind:expr; $desc:expr, $($arg:tt)*
-    ) => ({
-        $prom.err(new_err!($kind; $desc, $($arg)*));
+        $crate::ResultSink::send_result($sink, Ok($expr));
         return $ret;
     });
+}
+#[macro_export]
+macro_rules! s_err
+{
     (
-        ($prom:expr; $ret:expr) $kind:expr, $cause:expr; $desc:expr
+        ($sink:expr) $($err:tt)+
     ) => ({
-        $prom.err(new_err!($kind, $cause; $desc));
-        return $ret;
+        s_send_err!($sink, new_err!($($err)+));
+        return;
     });
     (
-        ($prom:expr; $ret:expr) $kind:expr, $cause:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr; $ret:expr) $($err:tt)+
     ) => ({
-        $prom.err(new_err!($kind, $cause; $desc, $($arg)*));
+        s_send_err!($sink, new_err!($($err)+));
         return $ret;
     });
 }
 
 
-#[cfg(feature = "alternate-future")]
 #[macro_export]
-macro_rules! p_attempt
+macro_rules! s_attempt
 {
     (
-        ($prom:expr) $expr:expr
+        ($sink:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $prom.err(from_err!(e));
+                s_send_err!($sink, from_err!(e));
                 return;
             },
         }
     );
     (
-        ($prom:expr) $expr:expr => $desc:expr
+        ($sink:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $prom.err(from_err!(e; $desc));
+                s_send_err!($sink, from_err!(e; $($desc)+));
                 return;
             },
         }
     );
+
     (
-        ($prom:expr) $expr:expr => $desc:expr, $($arg:tt)*
+        ($sink:expr; $ret:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $prom.err(from_err!(e; $desc, $($arg)*));
-                return;
+                s_send_err!($sink, from_err!(e));
+                return $ret;
             },
         }
     );
-
     (
-        ($prom:expr; $ret:expr) $expr:expr
+        ($sink:expr; $ret:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
             Err(e)  => {
-                $prom.err(from_err!(e));
+                s_send_err!($sink, from_err!(e; $($desc)+));
                 return $ret;
             },
         }
     );
+}
+
+
+#[macro_export]
+macro_rules! s_attempt_err
+{
     (
-        ($prom:expr; $ret:expr) $expr:expr => $desc:expr
+        ($sink:expr) $expr:expr => $kind_fn:expr; $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => {
-                $prom.err(from_err!(e; $desc));
-                return $ret;
-            },
+            Err(e)  => s_err!(($sink) $kind_fn(&e), e; $($desc)+),
         }
     );
     (
-        ($prom:expr; $ret:expr) $expr:expr => $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => {
-                $prom.err(from_err!(e; $desc, $($arg)*));
-                return $ret;
+            Err(e) => {
+                let kind = $kind_fn(&e);
+                let cause = $err_fn(e);
+                s_err!(($sink) kind, cause; $($desc)+)
             },
         }
     );
-}
-
 
-#[cfg(feature = "alternate-future")]
-#[macro_export]
-macro_rules! p_attempt_err
-{
     (
-        ($prom:ident) $expr:expr => $kind_fn:expr; $desc:expr
+        ($sink:expr; $ret:expr) $expr:expr => $kind_fn:expr; $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => p_err!(($prom) $kind_fn(&e), e; $desc),
+            Err(e)  => s_err!(($sink; $ret) $kind_fn(&e), e; $($desc)+),
         }
     );
     (
-        ($prom:ident) $expr:expr => $kind_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr; $ret:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => p_err!(($prom) $kind_fn(&e), e; $desc, $($arg)*),
+            Err(e) => {
+                let kind = $kind_fn(&e);
+                let cause = $err_fn(e);
+                s_err!(($sink; $ret) kind, cause; $($desc)+)
+            },
         }
     );
+}
+
+
+#[macro_export]
+macro_rules! s_attempt_continue
+{
     (
-        ($prom:ident) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr
+        ($sink:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                p_err!(($prom) kind, cause; $desc)
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e));
+                continue;
             },
         }
     );
     (
-        ($prom:ident) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                p_err!(($prom) kind, cause; $desc, $($arg)*)
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e; $($desc)+));
+                continue;
             },
         }
     );
+}
+
 
+#[macro_export]
+macro_rules! s_attempt_break
+{
     (
-        ($prom:ident; $ret:expr) $expr:expr => $kind_fn:expr; $desc:expr
+        ($sink:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => p_err!(($prom; $ret) $kind_fn(&e), e; $desc),
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e));
+                break;
+            },
         }
     );
     (
-        ($prom:ident; $ret:expr) $expr:expr => $kind_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e)  => p_err!(($prom; $ret) $kind_fn(&e), e; $desc, $($arg)*),
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e; $($desc)+));
+                break;
+            },
         }
     );
+
     (
-        ($prom:ident; $ret:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr
+        ($sink:expr; $val:expr) $expr:expr
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                p_err!(($prom; $ret) kind, cause; $desc)
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e));
+                break $val;
             },
         }
     );
     (
-        ($prom:ident; $ret:expr) $expr:expr => $kind_fn:expr, $err_fn:expr; $desc:expr, $($arg:tt)*
+        ($sink:expr; $val:expr) $expr:expr => $($desc:tt)+
     ) => (
         match $expr {
             Ok(val) => val,
-            Err(e) => {
-                let kind = $kind_fn(&e);
-                let cause = $err_fn(e);
-                p_err!(($prom; $ret) kind, cause; $desc, $($arg)*)
+            Err(e)  => {
+                s_send_err!($sink, from_err!(e; $($desc)+));
+                break $val;
             },
         }
     );
 }
 
 
+
+
+// Callbacks are called in place, the way `$func(result)` would, so a field or a reused
+// `FnMut` is only borrowed rather than moved into the sink. The hop they record is named
+// after `$func` rather than the closure around it.
+#[macro_export]
+macro_rules! f_ok
+{
+    (($func:expr) $($arg:tt)*) => (s_ok!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+    (($func:expr; $ret:expr) $($arg:tt)*) => (s_ok!(($crate::thread::Callback::new(|result| $func(result), stringify!($func)); $ret) $($arg)*));
+}
+#[macro_export]
+macro_rules! f_err
+{
+    (($func:expr) $($arg:tt)*) => (s_err!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+    (($func:expr; $ret:expr) $($arg:tt)*) => (s_err!(($crate::thread::Callback::new(|result| $func(result), stringify!($func)); $ret) $($arg)*));
+}
+#[macro_export]
+macro_rules! f_attempt
+{
+    (($func:expr) $($arg:tt)*) => (s_attempt!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+    (($func:expr; $ret:expr) $($arg:tt)*) => (s_attempt!(($crate::thread::Callback::new(|result| $func(result), stringify!($func)); $ret) $($arg)*));
+}
+#[macro_export]
+macro_rules! f_attempt_err
+{
+    (($func:expr) $($arg:tt)*) => (s_attempt_err!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+    (($func:expr; $ret:expr) $($arg:tt)*) => (s_attempt_err!(($crate::thread::Callback::new(|result| $func(result), stringify!($func)); $ret) $($arg)*));
+}
+#[macro_export]
+macro_rules! f_attempt_continue
+{
+    (($func:expr) $($arg:tt)*) => (s_attempt_continue!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+}
+#[macro_export]
+macro_rules! f_attempt_break
+{
+    (($func:expr) $($arg:tt)*) => (s_attempt_break!(($crate::thread::Callback::new(|result| $func(result), stringify!($func))) $($arg)*));
+    (($func:expr; $val:expr) $($arg:tt)*) => (s_attempt_break!(($crate::thread::Callback::new(|result| $func(result), stringify!($func)); $val) $($arg)*));
+}
+
+
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_ok { ($($arg:tt)*) => (s_ok!($($arg)*)); }
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_err { ($($arg:tt)*) => (s_err!($($arg)*)); }
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_attempt { ($($arg:tt)*) => (s_attempt!($($arg)*)); }
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_attempt_err { ($($arg:tt)*) => (s_attempt_err!($($arg)*)); }
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_attempt_continue { ($($arg:tt)*) => (s_attempt_continue!($($arg)*)); }
+#[cfg(feature = "alternate-future")]
+#[macro_export]
+macro_rules! p_attempt_break { ($($arg:tt)*) => (s_attempt_break!($($arg)*)); }
+
+
 #[derive(Clone)]
 pub struct StringErr (String);
 impl StringErr
@@ -839,6 +1354,257 @@ impl <E> Clone for ValueErr<E>
 }
 
 
+// An error rebuilt from a `Record` that crossed a process boundary. Its causes are remote
+// errors too, so `source()` walks the remote chain.
+pub struct RemoteErr
+{
+    record : report::Record,
+    cause  : Option<Box<RemoteErr>>,
+}
+impl RemoteErr
+{
+    pub fn from_record (mut record : report::Record) -> RemoteErr
+    {
+        let cause = record.cause.take().map(|c| Box::new(RemoteErr::from_record(*c)));
+        RemoteErr{record, cause}
+    }
+
+
+    #[cfg(feature = "json")]
+    pub fn from_json (json : &str) -> Result<RemoteErr, serde_json::Error>
+    {
+        report::Record::from_json(json).map(RemoteErr::from_record)
+    }
+
+
+    #[inline]
+    pub fn record (&self) -> &report::Record { &self.record }
+    #[inline]
+    pub fn kind_name (&self) -> Option<&str> { self.record.kind.as_ref().map(|k| &k[..]) }
+    #[inline]
+    pub fn code (&self) -> Option<&str> { self.record.code.as_ref().map(|c| &c[..]) }
+    #[inline]
+    pub fn msg (&self) -> &str { &self.record.msg }
+    #[inline]
+    pub fn frames (&self) -> &[report::Frame] { &self.record.frames }
+    #[inline]
+    pub fn fields (&self) -> &[(String, String)] { &self.record.fields }
+
+
+    fn write_remote (&self, fmt : &mut Formatter, options : &report::Options) -> Result<(), fmt::Error>
+    {
+        let record = &self.record;
+        if record.desc.is_empty() {
+            // only the text of a foreign error was recorded
+            writeln!(fmt, "{}", record.msg.trim_end())?;
+        } else {
+            match record.code {
+                Some(ref code) => options.write_headline(fmt, format_args!("{} [{}]: {}", record.desc, code, record.msg))?,
+                None           => options.write_headline(fmt, format_args!("{}: {}", record.desc, record.msg))?,
+            }
+            if options.verbosity == report::Verbosity::Brief {
+                return Ok(());
+            }
+            for (i, frame) in record.frames.iter().enumerate().rev() {
+                match record.notes.iter().find(|n| n.0 == i) {
+                    Some((_, note)) => options.write_detail(fmt, format_args!("    @ {}:{}: {}", frame.file, frame.line, note))?,
+                    None            => options.write_detail(fmt, format_args!("    @ {}:{}", frame.file, frame.line))?,
+                }
+            }
+            for hop in record.hops.iter().rev() {
+                options.write_detail(fmt, format_args!("    ~ {}", hop))?;
+            }
+            for scope in record.scopes.iter().rev() {
+                options.write_detail(fmt, format_args!("    in {}", scope))?;
+            }
+        }
+        match self.cause {
+            Some(ref cause) => cause.write_remote(fmt, options),
+            None            => Ok(()),
+        }
+    }
+}
+impl std::error::Error for RemoteErr
+{
+    #[inline]
+    fn description (&self) -> &str { "remote error" }
+
+
+    #[inline]
+    fn source (&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.cause {
+            Some(ref c) => Some(&**c),
+            None        => None,
+        }
+    }
+}
+#[cfg(feature = "json")]
+impl <'de> serde::Deserialize<'de> for RemoteErr
+{
+    fn deserialize <D> (deserializer : D) -> Result<RemoteErr, D::Error>
+        where D : serde::Deserializer<'de>
+    {
+        <report::Record as serde::Deserialize>::deserialize(deserializer).map(RemoteErr::from_record)
+    }
+}
+impl Debug for RemoteErr
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Display::fmt(self, fmt)
+    }
+}
+impl Display for RemoteErr
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        let options = report::options();
+        options.write_detail(fmt, format_args!("    ~ process boundary"))?;
+        self.write_remote(fmt, &options)
+    }
+}
+
+
+pub struct MultiErr (Vec<Box<dyn std::error::Error + Send + Sync>>);
+impl MultiErr
+{
+    #[inline]
+    pub fn new (errors : Vec<Box<dyn std::error::Error + Send + Sync>>) -> MultiErr { MultiErr(errors) }
+    
+    #[inline]
+    pub fn errors (&self) -> &[Box<dyn std::error::Error + Send + Sync>] { &self.0 }
+    #[inline]
+    pub fn into_errors (self) -> Vec<Box<dyn std::error::Error + Send + Sync>> { self.0 }
+}
+impl std::error::Error for MultiErr
+{
+    #[inline]
+    fn description (&self) -> &str { "multiple errors" }
+    
+    
+    #[inline]
+    fn source (&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.0.first() {
+            Some(b) => Some(&**b),
+            None    => None,
+        }
+    }
+}
+impl std::convert::From<Vec<Box<dyn std::error::Error + Send + Sync>>> for MultiErr
+{
+    #[inline]
+    fn from (errors : Vec<Box<dyn std::error::Error + Send + Sync>>) -> MultiErr { MultiErr(errors) }
+}
+impl Debug for MultiErr
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Display::fmt(self, fmt)
+    }
+}
+impl Display for MultiErr
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        writeln!(fmt, "{} errors:", self.0.len())?;
+        for (i, e) in self.0.iter().enumerate() {
+            let text = format!("{}", e);
+            writeln!(fmt, "[{}] {}", i, text.trim_end())?;
+        }
+        
+        Ok(())
+    }
+}
+
+
+#[cfg(feature = "anyhow")]
+pub struct AnyhowErr (anyhow::Error);
+#[cfg(feature = "anyhow")]
+impl AnyhowErr
+{
+    #[inline]
+    pub fn new (error : anyhow::Error) -> AnyhowErr { AnyhowErr(error) }
+    
+    #[inline]
+    pub fn inner (&self) -> &anyhow::Error { &self.0 }
+    #[inline]
+    pub fn into_inner (self) -> anyhow::Error { self.0 }
+}
+#[cfg(feature = "anyhow")]
+impl std::error::Error for AnyhowErr
+{
+    #[inline]
+    fn description (&self) -> &str { "anyhow error" }
+    
+    
+    #[inline]
+    fn source (&self) -> Option<&(dyn std::error::Error + 'static)> { self.0.source() }
+}
+#[cfg(feature = "anyhow")]
+impl Debug for AnyhowErr
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Debug::fmt(&self.0, fmt)
+    }
+}
+#[cfg(feature = "anyhow")]
+impl Display for AnyhowErr
+{
+    // `{:#}` shows every context in the chain and `{:?}` the backtrace as well
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        if report::options().verbosity == report::Verbosity::Full {
+            write!(fmt, "{:?}", self.0)
+        } else {
+            write!(fmt, "{:#}", self.0)
+        }
+    }
+}
+
+
+#[cfg(feature = "eyre")]
+pub struct EyreErr (eyre::Report);
+#[cfg(feature = "eyre")]
+impl EyreErr
+{
+    #[inline]
+    pub fn new (error : eyre::Report) -> EyreErr { EyreErr(error) }
+    
+    #[inline]
+    pub fn inner (&self) -> &eyre::Report { &self.0 }
+    #[inline]
+    pub fn into_inner (self) -> eyre::Report { self.0 }
+}
+#[cfg(feature = "eyre")]
+impl std::error::Error for EyreErr
+{
+    #[inline]
+    fn description (&self) -> &str { "eyre report" }
+    
+    
+    #[inline]
+    fn source (&self) -> Option<&(dyn std::error::Error + 'static)> { self.0.source() }
+}
+#[cfg(feature = "eyre")]
+impl Debug for EyreErr
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Debug::fmt(&self.0, fmt)
+    }
+}
+#[cfg(feature = "eyre")]
+impl Display for EyreErr
+{
+    // `{:#}` shows every context in the chain and `{:?}` the backtrace as well
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        if report::options().verbosity == report::Verbosity::Full {
+            write!(fmt, "{:?}", self.0)
+        } else {
+            write!(fmt, "{:#}", self.0)
+        }
+    }
+}
+
+
 #[macro_export]
 macro_rules! impl_from_error
 {
@@ -850,7 +1616,7 @@ macro_rules! impl_from_error
             fn from (error : $src) -> $dest
             {
                 let kind = $kind_fn(&error);
-                $crate::DetailedError::new(kind, Some(Box::new(error) as Box<::std::error::Error + Send + Sync>), ::std::borrow::ToOwned::to_owned($desc), "<unknown>", 0)
+                $crate::DetailedError::new(kind, Some(Box::new(error) as Box<dyn (::std::error::Error) + Send + Sync>), ::std::borrow::ToOwned::to_owned($desc), "<unknown>", 0)
             }
         }
         impl $crate::DetailedFromError<$src> for $dest
@@ -862,7 +1628,47 @@ macro_rules! impl_from_error
                     Some(s) => format!("{}: {}", $desc, s),
                     None    => ::std::borrow::ToOwned::to_owned($desc),
                 };
-                $crate::DetailedError::new(kind, Some(Box::new(error) as Box<::std::error::Error + Send + Sync>), desc, file, line)
+                $crate::DetailedError::new(kind, Some(Box::new(error) as Box<dyn (::std::error::Error) + Send + Sync>), desc, file, line)
+            }
+        }
+    );
+    (
+        <$src:ty, $dest:ty>; $desc:expr
+    ) => (
+        impl_from_error!{<$src, $dest> |e : &$src| $crate::kind::KindCause::infer_kind(e); $desc}
+    );
+}
+
+
+// Converts between two `error_items!` errors by mapping the kind, so the destination keeps
+// the source's message, cause and trace instead of boxing it as a cause.
+#[macro_export]
+macro_rules! impl_from_kind
+{
+    (
+        <$src:ty, $dest:ty> $kind_fn:expr
+    ) => (
+        impl ::std::convert::From<$src> for $dest
+        {
+            #[track_caller]
+            fn from (error : $src) -> $dest
+            {
+                let loc = ::std::panic::Location::caller();
+                let mut parts = $crate::ErrorParts::into_parts(error).map_kind(|kind| $kind_fn(&kind));
+                parts.trace.push((loc.file(), loc.line()));
+                $crate::ErrorParts::from_parts(parts)
+            }
+        }
+        impl $crate::DetailedFromError<$src> for $dest
+        {
+            fn from_error (error : $src, msg : Option<String>, file : &'static str, line : u32) -> $dest
+            {
+                let mut parts = $crate::ErrorParts::into_parts(error).map_kind(|kind| $kind_fn(&kind));
+                if let Some(msg) = msg {
+                    parts.notes.push((parts.trace.len(), msg));
+                }
+                parts.trace.push((file, line));
+                $crate::ErrorParts::from_parts(parts)
             }
         }
     )
@@ -880,7 +1686,7 @@ macro_rules! impl_from_val_error
             fn from_error (error : $src) -> $dest
             {
                 let kind = $kind_fn(&error);
-                $crate::DetailedError::new(kind, Some(Box::new($crate::api::error::ValueErr::new(error)) as Box<::std::error::Error + Send + Sync>), String::from_str($desc), "<unknown>", 0)
+                $crate::DetailedError::new(kind, Some(Box::new($crate::api::error::ValueErr::new(error)) as Box<dyn (::std::error::Error) + Send + Sync>), String::from_str($desc), "<unknown>", 0)
             }
         }
         impl $crate::DetailedFromError<$src> for $dest
@@ -892,7 +1698,7 @@ macro_rules! impl_from_val_error
                     Some(s) => format!("{}: {}", $desc, s),
                     None    => String::from_str($desc),
                 };
-                $crate::DetailedError::new(kind, Some(Box::new($crate::api::error::ValueErr::new(error)) as Box<::std::error::Error + Send + Sync>), desc, file, line)
+                $crate::DetailedError::new(kind, Some(Box::new($crate::api::error::ValueErr::new(error)) as Box<dyn (::std::error::Error) + Send + Sync>), desc, file, line)
             }
         }
     )
@@ -927,7 +1733,7 @@ macro_rules! impl_from_await_error
                             Some(s) => format!("{}: {}", $aborted_desc, s),
                             None    => ::std::borrow::ToOwned::to_owned($aborted_desc),
                         };
-                        $crate::DetailedError::new($aborted_kind, Some(Box::new(error) as Box<::std::error::Error + Send + Sync>), desc, file, line)
+                        $crate::DetailedError::new($aborted_kind, Some(Box::new(error) as Box<dyn (::std::error::Error) + Send + Sync>), desc, file, line)
                     },
                 }
             }
@@ -936,3 +1742,8 @@ macro_rules! impl_from_await_error
 }
 
 
+
+
+// declared after the macros because it defines its error type with `error_items!`
+#[cfg(feature = "json")]
+pub mod rpc;
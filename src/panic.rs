@@ -0,0 +1,209 @@
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use DetailedError;
+use report::{self, Format, Frame, Options, Verbosity};
+use scope;
+use thread;
+
+
+thread_local!(static LOCATION : RefCell<Option<(String, u32)>> = const { RefCell::new(None) });
+
+
+#[macro_export]
+macro_rules! catch
+{
+    (
+        $kind:expr; $expr:expr
+    ) => (
+        $crate::panic::catch_at($kind, || $expr, file!(), line!())
+    );
+}
+
+
+pub fn install_location_hook ()
+{
+    static INSTALL : Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Some(loc) = info.location() {
+                let loc = (loc.file().to_owned(), loc.line());
+                let _ = LOCATION.try_with(|l| *l.borrow_mut() = Some(loc));
+            }
+            previous(info);
+        }));
+    });
+}
+
+
+pub fn install_hook (options : Options)
+{
+    report::set_options(options);
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().map(|l| (l.file().to_owned(), l.line()));
+        let _ = LOCATION.try_with(|l| *l.borrow_mut() = location.clone());
+
+        let options = report::options();
+        let backtrace = match options.verbosity {
+            Verbosity::Brief  => None,
+            Verbosity::Normal => Some(Backtrace::capture()),
+            Verbosity::Full   => Some(Backtrace::force_capture()),
+        };
+        let backtrace = backtrace.and_then(|b| match b.status() {
+            BacktraceStatus::Captured => Some(b.to_string()),
+            _                         => None,
+        });
+        let report = PanicReport{
+            thread    : thread::current(),
+            msg       : payload_message(info.payload()),
+            location  : location.map(|(f, l)| Frame::new(f, l)),
+            scopes    : scope::snapshot(),
+            backtrace,
+        };
+
+        let _ = io::stderr().write_all(report.render(&options).as_bytes());
+    }));
+}
+
+
+pub fn take_location () -> Option<(String, u32)>
+{
+    LOCATION.try_with(|l| l.borrow_mut().take()).unwrap_or(None)
+}
+
+
+pub fn catch_at <F, T, E> (kind : <E as DetailedError>::Kind, f : F, file : &'static str, line : u32) -> Result<T, E>
+    where F : FnOnce() -> T, E : DetailedError
+{
+    take_location();
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(val)      => Ok(val),
+        Err(payload) => {
+            let cause = PanicErr::from_payload(&*payload, Some(thread::current()), take_location());
+            let msg = cause.msg().to_owned();
+            Err(E::new(kind, Some(Box::new(cause)), msg, file, line))
+        },
+    }
+}
+
+
+pub fn payload_message (payload : &(dyn Any + Send)) -> String
+{
+    if let Some(s) = payload.downcast_ref::<&'static str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+
+#[derive(Clone)]
+pub struct PanicErr
+{
+    msg      : String,
+    thread   : Option<String>,
+    location : Option<(String, u32)>,
+}
+impl PanicErr
+{
+    #[inline]
+    pub fn new (msg : String, thread : Option<String>, location : Option<(String, u32)>) -> PanicErr
+    {
+        PanicErr{msg, thread, location}
+    }
+
+
+    #[inline]
+    pub fn from_payload (payload : &(dyn Any + Send), thread : Option<String>, location : Option<(String, u32)>) -> PanicErr
+    {
+        PanicErr::new(payload_message(payload), thread, location)
+    }
+
+
+    #[inline]
+    pub fn msg (&self) -> &str { &self.msg }
+    #[inline]
+    pub fn thread (&self) -> Option<&str> { self.thread.as_deref() }
+    #[inline]
+    pub fn location (&self) -> Option<(&str, u32)> { self.location.as_ref().map(|&(ref f, l)| (&**f, l)) }
+}
+impl ::std::error::Error for PanicErr
+{
+    #[inline]
+    fn description (&self) -> &str { "panic" }
+}
+impl Debug for PanicErr
+{
+    #[inline]
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error> {
+        Display::fmt(self, fmt)
+    }
+}
+impl Display for PanicErr
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error>
+    {
+        if let Some(ref thread) = self.thread {
+            write!(fmt, "{} ", thread)?;
+        }
+        write!(fmt, "panicked")?;
+        if let Some((ref f, l)) = self.location {
+            write!(fmt, " at {}:{}", f, l)?;
+        }
+        write!(fmt, ": {}", self.msg)
+    }
+}
+
+
+#[cfg_attr(feature = "json", derive(Serialize))]
+struct PanicReport
+{
+    thread    : String,
+    msg       : String,
+    location  : Option<Frame>,
+    scopes    : Vec<String>,
+    backtrace : Option<String>,
+}
+impl PanicReport
+{
+    fn render (&self, options : &Options) -> String
+    {
+        match options.format {
+            Format::Text => self.render_text(options),
+            #[cfg(feature = "json")]
+            Format::Json => {
+                let mut out = ::serde_json::to_string(self).unwrap_or_default();
+                out.push('\n');
+                out
+            },
+        }
+    }
+
+
+    fn render_text (&self, options : &Options) -> String
+    {
+        let mut out = String::new();
+        let _ = options.write_headline(&mut out, format_args!("{} panicked: {}", self.thread, self.msg));
+        if options.verbosity == Verbosity::Brief {
+            return out;
+        }
+        if let Some(ref loc) = self.location {
+            let _ = options.write_detail(&mut out, format_args!("    @ {}:{}", loc.file, loc.line));
+        }
+        for scope in self.scopes.iter().rev() {
+            let _ = options.write_detail(&mut out, format_args!("    in {}", scope));
+        }
+        if let Some(ref backtrace) = self.backtrace {
+            out.push_str(backtrace);
+        }
+        out
+    }
+}
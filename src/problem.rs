@@ -0,0 +1,102 @@
+use std::fmt::Debug;
+
+use serde::Serialize;
+use serde_json::{self, Map, Value};
+
+use DetailedError;
+use kind::{Describe, HttpStatus};
+use report::Record;
+
+
+pub const CONTENT_TYPE : &str = "application/problem+json";
+
+// members a field can't become without clobbering the document's own
+const RESERVED : &[&str] = &["type", "title", "status", "detail", "instance", "code", "epitaph"];
+
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProblemOptions
+{
+    // joined with the kind's code to form the `type` URI; without it `type` is about:blank
+    pub type_base : Option<String>,
+    // adds the trace, scopes and causes as an `epitaph` member, which should never be
+    // sent to untrusted clients
+    pub debug     : bool,
+}
+
+
+// An RFC 7807 problem details document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Problem
+{
+    #[serde(rename = "type")]
+    pub type_uri   : String,
+    pub title      : String,
+    pub status     : u16,
+    pub detail     : String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance   : Option<String>,
+    #[serde(flatten)]
+    pub extensions : Map<String, Value>,
+}
+impl Problem
+{
+    pub fn from_error <E> (error : &E, options : &ProblemOptions) -> Problem
+        where E : DetailedError + 'static, <E as DetailedError>::Kind : Describe + HttpStatus + Debug
+    {
+        let code = error.code();
+        let type_uri = match (options.type_base.as_ref(), code) {
+            (Some(base), Some(code)) => format!("{}{}", base, code),
+            _                        => "about:blank".to_owned(),
+        };
+
+        let mut extensions = Map::new();
+        for (name, value) in error.fields() {
+            if !RESERVED.contains(&&name[..]) {
+                extensions.insert(name.clone(), Value::String(value.clone()));
+            }
+        }
+        if let Some(code) = code {
+            extensions.insert("code".to_owned(), Value::String(code.to_owned()));
+        }
+        if options.debug {
+            extensions.insert("epitaph".to_owned(), serde_json::to_value(Record::from_error(error)).expect("a record always serializes"));
+        }
+
+        Problem{
+            type_uri,
+            title:      error.kind().describe().to_owned(),
+            status:     error.kind().http_status(),
+            detail:     error.msg().to_owned(),
+            instance:   None,
+            extensions,
+        }
+    }
+
+
+    pub fn instance <S> (mut self, instance : S) -> Problem
+        where S : Into<String>
+    {
+        self.instance = Some(instance.into());
+        self
+    }
+
+
+    // a reserved name, such as `status`, is ignored just like a field of that name
+    pub fn extension <S, V> (mut self, name : S, value : V) -> Problem
+        where S : Into<String>, V : Serialize
+    {
+        let name = name.into();
+        if !RESERVED.contains(&&name[..]) {
+            let value = serde_json::to_value(value).unwrap_or(Value::Null);
+            self.extensions.insert(name, value);
+        }
+        self
+    }
+
+
+    pub fn to_json (&self) -> String
+    {
+        serde_json::to_string(self).expect("a problem always serializes")
+    }
+}
@@ -0,0 +1,134 @@
+use std::backtrace::Backtrace;
+use std::panic::Location;
+
+use alternate_future::{self, AwaitError, Future, Promise};
+
+use {DetailedFromError, ResultSink};
+
+
+#[track_caller]
+pub fn traced_promise_future <T, E> () -> (TracedPromise<T, E>, Future<Result<T, E>>)
+    where E : DetailedFromError<AwaitError> + DetailedFromError<E>
+{
+    let (p, f) = alternate_future::promise_future();
+    (TracedPromise::new(p), f)
+}
+
+
+// Breaking a promise reports where it was created, every `pass()` after that and where it
+// was dropped. `Drop` has no caller location, so the drop site is read off a backtrace and
+// only named in the message; without debug info it's missing, and `abandon()` records the
+// final site in the trace instead.
+pub struct TracedPromise <T, E>
+    where E : DetailedFromError<AwaitError> + DetailedFromError<E>
+{
+    promise : Option<Promise<Result<T, E>>>,
+    sites   : Vec<&'static Location<'static>>,
+}
+impl <T, E> TracedPromise<T, E>
+    where E : DetailedFromError<AwaitError> + DetailedFromError<E>
+{
+    #[track_caller]
+    pub fn new (promise : Promise<Result<T, E>>) -> TracedPromise<T, E>
+    {
+        TracedPromise{promise: Some(promise), sites: vec!(Location::caller())}
+    }
+
+
+    #[track_caller]
+    pub fn pass (mut self) -> TracedPromise<T, E>
+    {
+        self.sites.push(Location::caller());
+        self
+    }
+
+
+    #[track_caller]
+    pub fn abandon (mut self)
+    {
+        self.sites.push(Location::caller());
+        self.break_promise(None);
+    }
+
+
+    #[inline]
+    pub fn ok (mut self, val : T)
+    {
+        if let Some(p) = self.promise.take() {
+            p.ok(val);
+        }
+    }
+
+
+    #[inline]
+    pub fn err (mut self, error : E)
+    {
+        if let Some(p) = self.promise.take() {
+            p.err(error);
+        }
+    }
+
+
+    #[inline]
+    pub fn created (&self) -> (&'static str, u32) { (self.sites[0].file(), self.sites[0].line()) }
+
+
+    fn break_promise (&mut self, dropped : Option<String>)
+    {
+        if let Some(p) = self.promise.take() {
+            let msg = match dropped {
+                Some(at) => format!("promise dropped unfulfilled on {} at {}", ::thread::current(), at),
+                None     => format!("promise dropped unfulfilled on {}", ::thread::current()),
+            };
+
+            let created = self.sites[0];
+            let mut error = E::from_error(AwaitError::Broken, Some(msg), created.file(), created.line());
+            for site in &self.sites[1..] {
+                error = E::from_error(error, None, site.file(), site.line());
+            }
+            p.err(error);
+        }
+    }
+}
+impl <T, E> Drop for TracedPromise<T, E>
+    where E : DetailedFromError<AwaitError> + DetailedFromError<E>
+{
+    fn drop (&mut self)
+    {
+        if self.promise.is_some() {
+            self.break_promise(drop_site());
+        }
+    }
+}
+
+
+// The first frame with a source location outside std after the promise's own `drop`.
+fn drop_site () -> Option<String>
+{
+    let backtrace = Backtrace::force_capture().to_string();
+    let mut lines = backtrace.lines().map(str::trim);
+    lines.find(|l| l.contains("TracedPromise<") && l.ends_with("as core::ops::drop::Drop>::drop"))?;
+    // the location of `drop` itself
+    lines.next();
+
+    let at = lines.filter_map(|l| l.strip_prefix("at ")).find(|at| !at.starts_with("/rustc/"))?;
+    let at = at.trim_start_matches("./");
+    match at.rfind(':') {
+        Some(col) => Some(at[..col].to_owned()),
+        None      => Some(at.to_owned()),
+    }
+}
+impl <T, E> ResultSink<T, E> for TracedPromise<T, E>
+    where E : DetailedFromError<AwaitError> + DetailedFromError<E>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>)
+    {
+        match result {
+            Ok(val) => self.ok(val),
+            Err(e)  => self.err(e),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::any::TypeId;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::process::{self, ExitCode, Termination};
+use std::sync::RwLock;
+use std::thread;
+
+use {DetailedError, RemoteErr};
+use kind::{sysexits, ExitStatus};
+
+
+static OPTIONS : RwLock<Options> = RwLock::new(Options::DEFAULT);
+static RECORDERS : RwLock<Vec<(TypeId, Recorder)>> = RwLock::new(Vec::new());
+
+
+type Recorder = fn(&(dyn Error + 'static)) -> Option<Record>;
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Frame
+{
+    pub file : String,
+    pub line : u32,
+}
+impl Frame
+{
+    #[inline]
+    pub fn new <S> (file : S, line : u32) -> Frame
+        where S : Into<String>
+    {
+        Frame{file: file.into(), line}
+    }
+}
+
+
+// A snapshot of an error and its cause chain that can outlive the error itself. Causes that
+// are `error_items!` errors, registered errors or `RemoteErr`s are recorded in full; anything
+// else in the chain only keeps its text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Record
+{
+    pub desc   : String,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub kind   : Option<String>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code   : Option<String>,
+    pub msg    : String,
+    #[cfg_attr(feature = "json", serde(default))]
+    pub frames : Vec<Frame>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub notes  : Vec<(usize, String)>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub scopes : Vec<String>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub hops   : Vec<String>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub fields : Vec<(String, String)>,
+    #[cfg_attr(feature = "json", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub cause  : Option<Box<Record>>,
+}
+impl Record
+{
+    pub fn from_error <E> (error : &E) -> Record
+        where E : DetailedError + 'static, <E as DetailedError>::Kind : Debug
+    {
+        let mut chain = vec!(Record::detailed(error));
+        // the text of the last error without a record of its own, which may already render
+        // the plain causes below it; a cause that can be recorded in full always is
+        let mut rendered : Option<String> = None;
+        let mut next = error.source();
+        while let Some(cause) = next {
+            next = cause.source();
+            match Record::recorded(cause) {
+                Some(record) => {
+                    rendered = None;
+                    chain.push(record);
+                },
+                None         => {
+                    let record = Record::text(cause);
+                    if rendered.as_ref().is_some_and(|r| !record.msg.is_empty() && r.contains(&record.msg)) {
+                        continue;
+                    }
+                    rendered = Some(record.msg.clone());
+                    chain.push(record);
+                },
+            }
+        }
+
+        let mut record = chain.pop().unwrap();
+        while let Some(mut outer) = chain.pop() {
+            outer.cause = Some(Box::new(record));
+            record = outer;
+        }
+        record
+    }
+
+
+    fn detailed <E> (error : &E) -> Record
+        where E : DetailedError, <E as DetailedError>::Kind : Debug
+    {
+        Record::detailed_as(error, Some(format!("{:?}", error.kind())))
+    }
+
+
+    #[doc(hidden)]
+    #[allow(deprecated)]
+    pub fn detailed_as <E> (error : &E, kind : Option<String>) -> Record
+        where E : DetailedError
+    {
+        Record{
+            desc:   error.description().to_owned(),
+            kind,
+            code:   error.code().map(|c| c.to_owned()),
+            msg:    error.msg().to_owned(),
+            frames: error.trace().iter().map(|&(f, l)| Frame::new(f, l)).collect(),
+            notes:  error.notes().to_vec(),
+            scopes: error.scopes().to_vec(),
+            hops:   error.hops().iter().map(|h| h.to_string()).collect(),
+            fields: error.fields().to_vec(),
+            cause:  None,
+        }
+    }
+
+
+    fn recorded (error : &(dyn Error + 'static)) -> Option<Record>
+    {
+        if let Some(remote) = error.downcast_ref::<RemoteErr>() {
+            return Some(remote.record().clone());
+        }
+        let recorders = match RECORDERS.read() {
+            Ok(recorders) => recorders,
+            Err(e)        => e.into_inner(),
+        };
+        recorders.iter().filter_map(|r| (r.1)(error)).next()
+    }
+
+
+    fn text (error : &dyn Error) -> Record
+    {
+        Record{
+            desc:   String::new(),
+            kind:   None,
+            code:   None,
+            msg:    error.to_string(),
+            frames: Vec::new(),
+            notes:  Vec::new(),
+            scopes: Vec::new(),
+            hops:   Vec::new(),
+            fields: Vec::new(),
+            cause:  None,
+        }
+    }
+
+
+    #[cfg(feature = "json")]
+    pub fn to_json (&self) -> String
+    {
+        ::serde_json::to_string(self).expect("a record always serializes")
+    }
+
+
+    #[cfg(feature = "json")]
+    pub fn from_json (json : &str) -> Result<Record, ::serde_json::Error>
+    {
+        ::serde_json::from_str(json)
+    }
+}
+
+
+// Lets a `Record` recognise `E` anywhere in a cause chain, not only where its type is
+// known. `error_items!` errors register themselves the first time one is built, so this is
+// only needed for hand-written `DetailedError` types.
+pub fn register <E> ()
+    where E : DetailedError + 'static, <E as DetailedError>::Kind : Debug
+{
+    register_recorder(TypeId::of::<E>(), |e| e.downcast_ref::<E>().map(Record::detailed));
+}
+
+
+#[doc(hidden)]
+pub trait Recordable : DetailedError + 'static
+{
+    fn record_detailed (&self) -> Record;
+    fn register_detailed ();
+}
+
+
+#[doc(hidden)]
+pub fn register_recordable <E> ()
+    where E : Recordable
+{
+    register_recorder(TypeId::of::<E>(), |e| e.downcast_ref::<E>().map(Recordable::record_detailed));
+}
+
+
+fn register_recorder (id : TypeId, recorder : Recorder)
+{
+    let mut recorders = match RECORDERS.write() {
+        Ok(recorders) => recorders,
+        Err(e)        => e.into_inner(),
+    };
+    if !recorders.iter().any(|r| r.0 == id) {
+        recorders.push((id, recorder));
+    }
+}
+
+
+// `error_items!` can't require every kind to be `Debug`, so it names the kind through a
+// `&KindProbe` the same way it finds codes with `kind::CodeProbe`.
+#[doc(hidden)]
+pub struct KindProbe <'a, K : 'a> (pub &'a K);
+#[doc(hidden)]
+pub trait HasDebug
+{
+    fn probe_kind (&self) -> Option<String>;
+}
+impl <'a, K> HasDebug for KindProbe<'a, K>
+    where K : Debug
+{
+    #[inline]
+    fn probe_kind (&self) -> Option<String> { Some(format!("{:?}", self.0)) }
+}
+#[doc(hidden)]
+pub trait NoDebug
+{
+    #[inline]
+    fn probe_kind (&self) -> Option<String> { None }
+}
+impl <'a, 'b, K> NoDebug for &'b KindProbe<'a, K> {}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity
+{
+    Brief,
+    Normal,
+    Full,
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format
+{
+    Text,
+    #[cfg(feature = "json")]
+    Json,
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options
+{
+    pub verbosity : Verbosity,
+    pub color     : bool,
+    pub format    : Format,
+}
+impl Options
+{
+    pub const DEFAULT : Options = Options{verbosity: Verbosity::Normal, color: false, format: Format::Text};
+
+
+    pub fn write_headline <W> (&self, out : &mut W, args : fmt::Arguments) -> Result<(), fmt::Error>
+        where W : Write
+    {
+        if self.color {
+            writeln!(out, "\x1b[1;31m{}\x1b[0m", args)
+        } else {
+            writeln!(out, "{}", args)
+        }
+    }
+
+
+    pub fn write_detail <W> (&self, out : &mut W, args : fmt::Arguments) -> Result<(), fmt::Error>
+        where W : Write
+    {
+        if self.color {
+            writeln!(out, "\x1b[2m{}\x1b[0m", args)
+        } else {
+            writeln!(out, "{}", args)
+        }
+    }
+}
+impl Default for Options
+{
+    #[inline]
+    fn default () -> Options { Options::DEFAULT }
+}
+
+
+pub fn options () -> Options
+{
+    match OPTIONS.read() {
+        Ok(options) => *options,
+        Err(e)      => *e.into_inner(),
+    }
+}
+
+
+pub fn set_options (options : Options)
+{
+    match OPTIONS.write() {
+        Ok(mut o) => *o = options,
+        Err(e)    => *e.into_inner() = options,
+    }
+}
+
+
+// Returned from `main` to print the error and exit with its kind's `ExitStatus`, either
+// as `main () -> Result<(), Report<E>>` or as the return type itself. std's `Termination`
+// for `Result` prints the error with `{:?}` and then exits with 1, so a report printed with
+// `{:?}` on the main thread exits with the kind's code as soon as it's dropped, before std
+// gets the chance. Print `error()` instead to keep such a report around.
+pub struct Report <E>
+{
+    error   : Option<E>,
+    code    : u8,
+    printed : Cell<bool>,
+}
+impl <E> Report<E>
+    where E : DetailedError, <E as DetailedError>::Kind : ExitStatus
+{
+    #[inline]
+    pub fn run <F> (main : F) -> Report<E>
+        where F : FnOnce() -> Result<(), E>
+    {
+        Report::from(main())
+    }
+
+
+    #[inline]
+    pub fn error (&self) -> Option<&E> { self.error.as_ref() }
+    #[inline]
+    pub fn exit_code (&self) -> u8 { self.code }
+}
+impl <E> From<E> for Report<E>
+    where E : DetailedError, <E as DetailedError>::Kind : ExitStatus
+{
+    #[inline]
+    fn from (error : E) -> Report<E>
+    {
+        Report{code: error.kind().exit_code(), error: Some(error), printed: Cell::new(false)}
+    }
+}
+impl <E> From<Result<(), E>> for Report<E>
+    where E : DetailedError, <E as DetailedError>::Kind : ExitStatus
+{
+    #[inline]
+    fn from (result : Result<(), E>) -> Report<E>
+    {
+        match result {
+            Ok(())     => Report{error: None, code: sysexits::OK, printed: Cell::new(false)},
+            Err(error) => Report::from(error),
+        }
+    }
+}
+impl <E> Termination for Report<E>
+    where E : Display
+{
+    fn report (self) -> ExitCode
+    {
+        if let Some(ref e) = self.error {
+            eprint!("{}", e);
+        }
+        ExitCode::from(self.code)
+    }
+}
+impl <E> Debug for Report<E>
+    where E : Display
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error>
+    {
+        self.printed.set(true);
+        match self.error {
+            Some(ref e) => Display::fmt(e, fmt),
+            None        => Ok(()),
+        }
+    }
+}
+impl <E> Drop for Report<E>
+{
+    fn drop (&mut self)
+    {
+        if self.printed.get() && self.error.is_some() && thread::current().name() == Some("main") {
+            process::exit(i32::from(self.code));
+        }
+    }
+}
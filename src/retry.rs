@@ -0,0 +1,165 @@
+use std::error::Error;
+use std::panic::Location;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use DetailedError;
+use MultiErr;
+
+
+#[macro_export]
+macro_rules! retry
+{
+    (
+        $policy:expr; $expr:expr
+    ) => (
+        $crate::retry::retry_at($policy, |kind| $crate::kind::Classify::is_retryable(kind), || $expr, file!(), line!())
+    );
+    (
+        $policy:expr; $($kind:pat)|+ => $expr:expr
+    ) => (
+        $crate::retry::retry_at($policy, |kind| match *kind { $($kind)|+ => true, _ => false }, || $expr, file!(), line!())
+    );
+}
+
+
+pub struct Policy
+{
+    attempts : u32,
+    base     : Duration,
+    cap      : Duration,
+    jitter   : f64,
+    seed     : u64,
+    sleep    : Box<dyn FnMut(Duration)>,
+}
+impl Policy
+{
+    pub fn new (attempts : u32) -> Policy
+    {
+        let seed = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(now) => now.as_secs() ^ now.subsec_nanos() as u64,
+            Err(_)  => 0,
+        };
+        Policy{
+            attempts,
+            base:     Duration::from_millis(100),
+            cap:      Duration::from_secs(30),
+            jitter:   0.5,
+            seed,
+            sleep:    Box::new(thread::sleep),
+        }
+    }
+
+
+    pub fn backoff (mut self, base : Duration, cap : Duration) -> Policy
+    {
+        self.base = base;
+        self.cap = cap;
+        self
+    }
+
+
+    // the fraction of each delay that is randomised; 0 gives plain exponential backoff, as
+    // does NaN
+    pub fn jitter (mut self, jitter : f64) -> Policy
+    {
+        self.jitter = if jitter.is_nan() { 0.0 } else { jitter.clamp(0.0, 1.0) };
+        self
+    }
+
+
+    pub fn seed (mut self, seed : u64) -> Policy
+    {
+        self.seed = seed;
+        self
+    }
+
+
+    pub fn sleep <F> (mut self, sleep : F) -> Policy
+        where F : FnMut(Duration) + 'static
+    {
+        self.sleep = Box::new(sleep);
+        self
+    }
+
+
+    #[inline]
+    pub fn attempts (&self) -> u32 { self.attempts }
+
+
+    pub fn delays (&self) -> Delays
+    {
+        // xorshift gets stuck on zero, so nudge the seed away from it
+        Delays{base: self.base, cap: self.cap, jitter: self.jitter, state: self.seed | 1, retry: 0}
+    }
+}
+
+
+pub struct Delays
+{
+    base   : Duration,
+    cap    : Duration,
+    jitter : f64,
+    state  : u64,
+    retry  : u32,
+}
+impl Iterator for Delays
+{
+    type Item = Duration;
+
+
+    fn next (&mut self) -> Option<Duration>
+    {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let unit = (self.state >> 11) as f64 / (1u64 << 53) as f64;
+
+        let delay = match self.base.checked_mul(1 << self.retry.min(31)) {
+            Some(delay) => delay.min(self.cap),
+            None        => self.cap,
+        };
+        self.retry += 1;
+        Some(delay.mul_f64(1.0 - self.jitter * unit))
+    }
+}
+
+
+#[track_caller]
+pub fn retry_with <P, F, T, E> (policy : Policy, retryable : P, f : F) -> Result<T, E>
+    where P : FnMut(&<E as DetailedError>::Kind) -> bool, F : FnMut() -> Result<T, E>, E : DetailedError + Send + Sync + 'static, <E as DetailedError>::Kind : Clone
+{
+    let loc = Location::caller();
+    retry_at(policy, retryable, f, loc.file(), loc.line())
+}
+
+
+pub fn retry_at <P, F, T, E> (mut policy : Policy, mut retryable : P, mut f : F, file : &'static str, line : u32) -> Result<T, E>
+    where P : FnMut(&<E as DetailedError>::Kind) -> bool, F : FnMut() -> Result<T, E>, E : DetailedError + Send + Sync + 'static, <E as DetailedError>::Kind : Clone
+{
+    let mut delays = policy.delays();
+    let mut failures : Vec<Box<dyn Error + Send + Sync>> = Vec::new();
+    loop {
+        let error = match f() {
+            Ok(val) => return Ok(val),
+            Err(e)  => e,
+        };
+
+        let attempts = failures.len() as u32 + 1;
+        if attempts < policy.attempts && retryable(error.kind()) {
+            failures.push(Box::new(error));
+            if let Some(delay) = delays.next() {
+                (policy.sleep)(delay);
+            }
+            continue;
+        }
+
+        // nothing was retried, so there is nothing to add to the original error
+        if failures.is_empty() {
+            return Err(error);
+        }
+        let kind = error.kind().clone();
+        failures.push(Box::new(error));
+        return Err(E::new(kind, Some(Box::new(MultiErr::new(failures))), format!("gave up after {} attempts", attempts), file, line));
+    }
+}
@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::panic::Location;
+
+use serde_json::{self, Value};
+
+use {DetailedError, ErrorParts, RemoteErr};
+use kind::{GrpcCode, HttpStatus, RpcKind};
+use report::Record;
+
+
+// What a received status or error object says about the remote error's kind. Forwarding
+// an `RpcError` keeps the codes it arrived with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteKind
+{
+    pub grpc     : GrpcCode,
+    pub json_rpc : i64,
+    pub name     : Option<String>,
+    pub code     : Option<String>,
+}
+impl RemoteKind
+{
+    fn new (grpc : GrpcCode, json_rpc : i64, record : Option<&Record>) -> RemoteKind
+    {
+        RemoteKind{
+            grpc,
+            json_rpc,
+            name:     record.and_then(|r| r.kind.clone()),
+            code:     record.and_then(|r| r.code.clone()),
+        }
+    }
+}
+impl HttpStatus for RemoteKind
+{
+    #[inline]
+    fn http_status (&self) -> u16 { self.grpc.http_status() }
+}
+impl RpcKind for RemoteKind
+{
+    #[inline]
+    fn grpc_code (&self) -> GrpcCode { self.grpc }
+    #[inline]
+    fn json_rpc_code (&self) -> i64 { self.json_rpc }
+}
+
+error_items!{RpcError<Kind = RemoteKind> "Remote Error"}
+
+
+// Keeps the fields the remote error was sent with, so forwarding it sends them on again.
+fn remote_error (kind : RemoteKind, record : Option<Record>, message : String, file : &'static str, line : u32) -> RpcError
+{
+    let fields = record.as_ref().map(|r| r.fields.clone()).unwrap_or_default();
+    let cause = record.map(|r| Box::new(RemoteErr::from_record(r)) as Box<dyn Error + Send + Sync>);
+    let error : RpcError = DetailedError::new(kind, cause, message, file, line);
+    fields.into_iter().fold(error, |e, (name, value)| e.with_field(name, value))
+}
+
+
+// A framework-independent gRPC status. `details` holds the error's `Record`, with its
+// trace and fields, as JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Status
+{
+    pub code    : GrpcCode,
+    pub message : String,
+    pub details : Vec<u8>,
+}
+impl Status
+{
+    pub fn from_error <E> (error : &E) -> Status
+        where E : DetailedError + 'static, <E as DetailedError>::Kind : RpcKind + Debug
+    {
+        Status{
+            code:    error.kind().grpc_code(),
+            message: error.msg().to_owned(),
+            details: Record::from_error(error).to_json().into_bytes(),
+        }
+    }
+
+
+    pub fn record (&self) -> Option<Record>
+    {
+        serde_json::from_slice(&self.details).ok()
+    }
+
+
+    #[track_caller]
+    pub fn into_error (self) -> RpcError
+    {
+        let loc = Location::caller();
+        let record = self.record();
+        let kind = RemoteKind::new(self.code, -32000, record.as_ref());
+        remote_error(kind, record, self.message, loc.file(), loc.line())
+    }
+}
+
+
+// A JSON-RPC 2.0 error object. `data` holds the error's `Record`, with its trace and fields.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonRpcError
+{
+    pub code    : i64,
+    pub message : String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data    : Option<Value>,
+}
+impl JsonRpcError
+{
+    pub fn from_error <E> (error : &E) -> JsonRpcError
+        where E : DetailedError + 'static, <E as DetailedError>::Kind : RpcKind + Debug
+    {
+        JsonRpcError{
+            code:    error.kind().json_rpc_code(),
+            message: error.msg().to_owned(),
+            data:    serde_json::to_value(Record::from_error(error)).ok(),
+        }
+    }
+
+
+    pub fn record (&self) -> Option<Record>
+    {
+        self.data.as_ref().and_then(|d| serde_json::from_value(d.clone()).ok())
+    }
+
+
+    #[track_caller]
+    pub fn into_error (self) -> RpcError
+    {
+        let loc = Location::caller();
+        let record = self.record();
+        let kind = RemoteKind::new(GrpcCode::Unknown, self.code, record.as_ref());
+        remote_error(kind, record, self.message, loc.file(), loc.line())
+    }
+}
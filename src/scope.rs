@@ -0,0 +1,114 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+
+// each entry is tagged with the guard that pushed it, or 0 for a `Scoped` future's own
+thread_local!(static SCOPES : RefCell<Vec<(usize, String)>> = const { RefCell::new(Vec::new()) });
+thread_local!(static NEXT_ID : Cell<usize> = const { Cell::new(1) });
+
+
+#[macro_export]
+macro_rules! scope
+{
+    (
+        $desc:expr
+    ) => (
+        $crate::scope::ScopeGuard::push(::std::borrow::ToOwned::to_owned($desc))
+    );
+    (
+        $desc:expr, $($arg:tt)*
+    ) => (
+        $crate::scope::ScopeGuard::push(format!($desc, $($arg)*))
+    );
+}
+
+
+pub fn snapshot () -> Vec<String>
+{
+    SCOPES.try_with(|s| s.borrow().iter().map(|e| e.1.clone()).collect()).unwrap_or_default()
+}
+
+
+#[must_use]
+pub struct ScopeGuard
+{
+    id       : usize,
+    _no_send : PhantomData<*const ()>,
+}
+impl ScopeGuard
+{
+    pub fn push (desc : String) -> ScopeGuard
+    {
+        let id = NEXT_ID.with(|n| { let id = n.get(); n.set(id + 1); id });
+        SCOPES.with(|s| s.borrow_mut().push((id, desc)));
+        ScopeGuard{id, _no_send: PhantomData}
+    }
+}
+impl Drop for ScopeGuard
+{
+    fn drop (&mut self)
+    {
+        // only this guard's own entry, so that dropping guards out of order leaves the
+        // scopes of the others alone
+        let id = self.id;
+        let _ = SCOPES.try_with(|s| {
+            let mut s = s.borrow_mut();
+            if let Some(i) = s.iter().rposition(|e| e.0 == id) {
+                s.remove(i);
+            }
+        });
+    }
+}
+
+
+pub struct Scoped <F>
+{
+    future : F,
+    scopes : Vec<String>,
+}
+impl <F> Scoped<F>
+{
+    #[inline]
+    pub fn new (future : F, desc : String) -> Scoped<F>
+    {
+        Scoped{future, scopes: vec!(desc)}
+    }
+}
+impl <F> Future for Scoped<F>
+    where F : Future
+{
+    type Output = <F as Future>::Output;
+
+
+    fn poll (self : Pin<&mut Self>, cx : &mut Context) -> Poll<<F as Future>::Output>
+    {
+        // `future` is structurally pinned and never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        let scopes = &this.scopes;
+
+        let depth = SCOPES.with(|s| {
+            let mut s = s.borrow_mut();
+            let depth = s.len();
+            s.extend(scopes.iter().map(|desc| (0, desc.clone())));
+            depth
+        });
+        // restored by a guard so that a panicking poll doesn't leave the scopes behind
+        let _restore = Restore(depth);
+        future.poll(cx)
+    }
+}
+
+
+struct Restore (usize);
+impl Drop for Restore
+{
+    fn drop (&mut self)
+    {
+        let depth = self.0;
+        let _ = SCOPES.try_with(|s| s.borrow_mut().truncate(depth));
+    }
+}
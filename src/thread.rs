@@ -0,0 +1,289 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::sync::mpsc;
+use std::thread;
+
+use {DetailedError, DetailedFromError, ResultSink};
+use panic::PanicErr;
+
+
+pub fn current () -> String
+{
+    describe(&thread::current())
+}
+
+
+pub fn describe (thread : &thread::Thread) -> String
+{
+    match thread.name() {
+        Some(name) => format!("thread '{}'", name),
+        None       => format!("thread {:?}", thread.id()),
+    }
+}
+
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hop
+{
+    pub from : String,
+    pub to   : Option<String>,
+    pub via  : Cow<'static, str>,
+    pub file : &'static str,
+    pub line : u32,
+    pub at   : usize,
+}
+impl Hop
+{
+    pub fn new <V> (via : V, file : &'static str, line : u32) -> Hop
+        where V : Into<Cow<'static, str>>
+    {
+        Hop{from: current(), to: None, via: via.into(), file, line, at: 0}
+    }
+}
+impl Display for Hop
+{
+    fn fmt (&self, fmt : &mut Formatter) -> Result<(), fmt::Error>
+    {
+        match self.to {
+            Some(ref to) => write!(fmt, "{} -> {} via {} @ {}:{}", self.from, to, self.via, self.file, self.line),
+            None         => write!(fmt, "{} via {} @ {}:{}", self.from, self.via, self.file, self.line),
+        }
+    }
+}
+
+
+pub trait RecordHop : Sized
+{
+    fn record_hop (self, hop : Hop) -> Self;
+    #[inline]
+    fn complete_hop (self, _ : String) -> Self { self }
+}
+
+
+impl RecordHop for Box<dyn Error>
+{
+    #[inline]
+    fn record_hop (self, _ : Hop) -> Box<dyn Error> { self }
+}
+impl RecordHop for Box<dyn Error + Send + Sync>
+{
+    #[inline]
+    fn record_hop (self, _ : Hop) -> Box<dyn Error + Send + Sync> { self }
+}
+#[cfg(feature = "anyhow")]
+impl RecordHop for ::anyhow::Error
+{
+    #[inline]
+    fn record_hop (self, hop : Hop) -> ::anyhow::Error { self.context(hop) }
+}
+#[cfg(feature = "eyre")]
+impl RecordHop for ::eyre::Report
+{
+    #[inline]
+    fn record_hop (self, hop : Hop) -> ::eyre::Report { self.wrap_err(hop) }
+}
+
+
+// Only sinks that hand the result on to another execution context get a hop; a plain
+// closure runs right here, so it would only ever record a hop to nowhere.
+#[inline]
+pub fn sink_hop <S, T, E> (sink : &S, via : &'static str, file : &'static str, line : u32) -> Option<Hop>
+    where S : ResultSink<T, E>
+{
+    if sink.crosses_threads() { Some(Hop::new(sink.name().unwrap_or(via), file, line)) } else { None }
+}
+
+
+// Ties the error to the sink's error type, so that it's known before the hop is recorded.
+#[doc(hidden)]
+#[inline]
+pub fn sink_err <S, T, E> (_ : &S, error : E) -> E
+    where S : ResultSink<T, E>
+{
+    error
+}
+
+
+// The `s_*` macros work with any error type, so rather than requiring `RecordHop` they
+// probe for it the same way `kind::CodeProbe` probes for `ErrorCode`: `&HopProbe` picks
+// `HasHop` when the error can record a hop and falls back to the autoref'd `NoHop`.
+#[doc(hidden)]
+pub struct HopProbe <E> (PhantomData<E>);
+impl <E> HopProbe<E>
+{
+    #[inline]
+    pub fn of (_ : &E) -> HopProbe<E> { HopProbe(PhantomData) }
+}
+#[doc(hidden)]
+pub trait HasHop <E>
+{
+    fn probe_hop (&self) -> fn(E, Hop) -> E;
+}
+impl <E> HasHop<E> for HopProbe<E>
+    where E : RecordHop
+{
+    #[inline]
+    fn probe_hop (&self) -> fn(E, Hop) -> E { E::record_hop }
+}
+#[doc(hidden)]
+pub trait NoHop <E>
+{
+    fn probe_hop (&self) -> fn(E, Hop) -> E;
+}
+impl <E> NoHop<E> for &HopProbe<E>
+{
+    #[inline]
+    fn probe_hop (&self) -> fn(E, Hop) -> E { |error, _| error }
+}
+
+
+// What the `f_*` macros hand to the `s_*` ones, so that a hop names the callback rather
+// than the closure calling it.
+pub struct Callback <F>
+{
+    func : F,
+    name : &'static str,
+}
+impl <F> Callback<F>
+{
+    #[inline]
+    pub fn new (func : F, name : &'static str) -> Callback<F> { Callback{func, name} }
+}
+impl <T, E, F> ResultSink<T, E> for Callback<F>
+    where F : FnOnce(Result<T, E>)
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn name (&self) -> Option<&'static str> { Some(self.name) }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { (self.func)(result) }
+}
+
+
+pub fn traced_channel <T, E, N> (name : N) -> (TracedSender<T, E>, TracedReceiver<T, E>)
+    where E : RecordHop, N : Into<Cow<'static, str>>
+{
+    let (tx, rx) = mpsc::channel();
+    let via : Cow<'static, str> = format!("channel '{}'", name.into()).into();
+    (TracedSender{tx, via: via.clone()}, TracedReceiver{rx, via})
+}
+
+
+pub struct TracedSender <T, E>
+{
+    tx  : mpsc::Sender<Result<T, E>>,
+    via : Cow<'static, str>,
+}
+impl <T, E> TracedSender<T, E>
+    where E : RecordHop
+{
+    #[track_caller]
+    pub fn send (&self, result : Result<T, E>) -> Result<(), mpsc::SendError<Result<T, E>>>
+    {
+        let loc = Location::caller();
+        self.tx.send(result.map_err(|e| e.record_hop(Hop::new(self.via.clone(), loc.file(), loc.line()))))
+    }
+}
+impl <T, E> Clone for TracedSender<T, E>
+{
+    #[inline]
+    fn clone (&self) -> TracedSender<T, E> { TracedSender{tx: self.tx.clone(), via: self.via.clone()} }
+}
+// the `s_*` macros have already recorded the hop by the time a sink sees the result
+impl <T, E> ResultSink<T, E> for TracedSender<T, E>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.tx.send(result); }
+}
+impl <T, E> ResultSink<T, E> for &TracedSender<T, E>
+{
+    #[inline]
+    fn crosses_threads (&self) -> bool { true }
+    #[inline]
+    fn send_result (self, result : Result<T, E>) { let _ = self.tx.send(result); }
+}
+
+
+pub struct TracedReceiver <T, E>
+{
+    rx  : mpsc::Receiver<Result<T, E>>,
+    via : Cow<'static, str>,
+}
+impl <T, E> TracedReceiver<T, E>
+    where E : RecordHop
+{
+    pub fn recv (&self) -> Result<Result<T, E>, mpsc::RecvError>
+    {
+        self.rx.recv().map(|r| r.map_err(|e| e.complete_hop(current())))
+    }
+
+
+    pub fn try_recv (&self) -> Result<Result<T, E>, mpsc::TryRecvError>
+    {
+        self.rx.try_recv().map(|r| r.map_err(|e| e.complete_hop(current())))
+    }
+
+
+    #[inline]
+    pub fn via (&self) -> &str { &self.via }
+}
+
+
+#[track_caller]
+pub fn spawn <F, T, E> (f : F) -> JoinHandle<T, E>
+    where F : FnOnce() -> Result<T, E> + Send + 'static, T : Send + 'static, E : Send + 'static
+{
+    JoinHandle{inner: thread::spawn(f), spawned: Location::caller()}
+}
+
+
+#[track_caller]
+pub fn spawn_named <F, T, E> (name : String, f : F) -> io::Result<JoinHandle<T, E>>
+    where F : FnOnce() -> Result<T, E> + Send + 'static, T : Send + 'static, E : Send + 'static
+{
+    let spawned = Location::caller();
+    let inner = thread::Builder::new().name(name).spawn(f)?;
+    Ok(JoinHandle{inner, spawned})
+}
+
+
+pub struct JoinHandle <T, E>
+{
+    inner   : thread::JoinHandle<Result<T, E>>,
+    spawned : &'static Location<'static>,
+}
+impl <T, E> JoinHandle<T, E>
+    where E : DetailedError + DetailedFromError<E> + RecordHop
+{
+    #[track_caller]
+    pub fn join (self, kind : <E as DetailedError>::Kind) -> Result<T, E>
+    {
+        let joined = Location::caller();
+        let from = describe(self.inner.thread());
+        let hop = Hop{from: from.clone(), to: Some(current()), via: "join".into(), file: joined.file(), line: joined.line(), at: 0};
+
+        let error = match self.inner.join() {
+            Ok(Ok(val)) => return Ok(val),
+            Ok(Err(e))  => e,
+            Err(payload) => {
+                let cause = PanicErr::from_payload(&*payload, Some(from), None);
+                let msg = format!("thread panicked: {}", cause.msg());
+                E::new(kind, Some(Box::new(cause)), msg, self.spawned.file(), self.spawned.line())
+            },
+        };
+        Err(E::from_error(error.record_hop(hop), None, joined.file(), joined.line()))
+    }
+
+
+    #[inline]
+    pub fn thread (&self) -> &thread::Thread { self.inner.thread() }
+    #[inline]
+    pub fn spawned (&self) -> (&'static str, u32) { (self.spawned.file(), self.spawned.line()) }
+}
@@ -0,0 +1,122 @@
+#![cfg(any(feature = "anyhow", feature = "eyre"))]
+
+#[macro_use]
+extern crate epitaph;
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "eyre")]
+extern crate eyre;
+
+use epitaph::DetailedError;
+
+
+fn fail_func () -> Result<(), Error>
+{
+    err!(ErrorKind::ErrorOne; "Error One");
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    ErrorOne,
+    ErrorTwo,
+    ErrorThree,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn anyhow_dest ()
+{
+    fn anyhow_impl () -> Result<(), anyhow::Error>
+    {
+        attempt!(fail_func() => "Context");
+
+        Ok(())
+    }
+
+    let err = anyhow_impl().err().unwrap();
+    let ctx = err.downcast_ref::<epitaph::TraceContext>().unwrap();
+    assert_eq!(ctx.msg(), Some("Context"));
+    assert_eq!(ctx.trace().len(), 2);
+
+    let inner = err.downcast_ref::<Error>().unwrap();
+    assert_eq!(*inner.kind(), ErrorKind::ErrorOne);
+    assert_eq!(inner.trace().len(), 2);
+}
+
+
+#[cfg(feature = "eyre")]
+#[test]
+fn eyre_dest ()
+{
+    fn eyre_impl () -> Result<(), eyre::Report>
+    {
+        attempt!(fail_func());
+
+        Ok(())
+    }
+
+    let err = eyre_impl().err().unwrap();
+    let inner = err.downcast_ref::<Error>().unwrap();
+    assert_eq!(inner.trace().len(), 2);
+}
+
+
+error_items!{ForeignError<Kind = ErrorKind, Foreign = ErrorKind::ErrorThree> "Foreign Test Error"}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn anyhow_cause ()
+{
+    use std::error::Error as StdError;
+
+    fn anyhow_fail () -> Result<(), anyhow::Error>
+    {
+        Err(anyhow::Error::new(epitaph::StringErr::from_str("root")).context("middle"))
+    }
+    fn foreign_impl () -> Result<(), ForeignError>
+    {
+        attempt!(anyhow_fail() => "Importing");
+
+        Ok(())
+    }
+
+    let err = foreign_impl().err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::ErrorThree);
+
+    let cause = err.source().unwrap().downcast_ref::<epitaph::AnyhowErr>().unwrap();
+    assert_eq!(cause.inner().chain().count(), 2);
+    assert_eq!(format!("{}", cause.source().unwrap()), "root");
+    assert_eq!(format!("{}", cause), "middle: root");
+    assert_eq!(err.msg(), "Importing");
+    assert!(err.to_string().ends_with("\nmiddle: root"));
+}
+
+
+#[cfg(feature = "eyre")]
+#[test]
+fn eyre_cause ()
+{
+    use std::error::Error as StdError;
+
+    fn eyre_fail () -> Result<(), eyre::Report>
+    {
+        Err(eyre::Report::new(epitaph::StringErr::from_str("root")).wrap_err("middle"))
+    }
+    fn foreign_impl () -> Result<(), ForeignError>
+    {
+        attempt!(eyre_fail());
+
+        Ok(())
+    }
+
+    let err = foreign_impl().err().unwrap();
+    let cause = err.source().unwrap().downcast_ref::<epitaph::EyreErr>().unwrap();
+    assert_eq!(cause.inner().chain().count(), 2);
+    assert_eq!(err.msg(), "eyre report");
+    assert!(err.to_string().ends_with("\nmiddle: root"));
+}
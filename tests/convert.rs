@@ -0,0 +1,116 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::error::Error as StdError;
+
+use epitaph::DetailedError;
+
+
+mod db
+{
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub enum ErrorKind
+    {
+        Missing,
+        Locked,
+    }
+
+    error_items!{Error<Kind = ErrorKind> "Db Error"}
+
+
+    pub fn fetch () -> Result<(), Error>
+    {
+        err!(ErrorKind::Missing, epitaph::StringErr::from_str("no row"); "Row 3 missing");
+    }
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    NotFound,
+    Unavailable,
+}
+
+error_items!{Error<Kind = ErrorKind> "Service Error"}
+
+impl_from_kind!{<db::Error, Error> |kind : &db::ErrorKind| match *kind {
+    db::ErrorKind::Missing => ErrorKind::NotFound,
+    db::ErrorKind::Locked  => ErrorKind::Unavailable,
+}}
+
+
+fn attempted () -> Result<(), Error>
+{
+    attempt!(db::fetch() => "Loading user");
+    Ok(())
+}
+
+
+fn questioned () -> Result<(), Error>
+{
+    db::fetch()?;
+    Ok(())
+}
+
+
+#[test]
+fn kind_mapping ()
+{
+    let err = attempted().unwrap_err();
+    assert_eq!(*err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.msg(), "Row 3 missing");
+    assert_eq!(err.trace().len(), 2);
+    assert_eq!(err.trace()[0], ("tests/convert.rs", 23));
+    assert_eq!(err.trace()[1], ("tests/convert.rs", 45));
+    assert!(err.source().unwrap().downcast_ref::<epitaph::StringErr>().is_some());
+
+    let text = err.to_string();
+    assert!(text.starts_with("Service Error: Row 3 missing\n    @ tests/convert.rs:45: Loading user\n    @ tests/convert.rs:23\n"));
+
+    let err = questioned().unwrap_err();
+    assert_eq!(err.trace(), &[("tests/convert.rs", 23), ("tests/convert.rs", 52)]);
+}
+
+
+impl epitaph::kind::IoKind for ErrorKind
+{
+    fn io_kind (&self) -> std::io::ErrorKind
+    {
+        match *self {
+            ErrorKind::NotFound    => std::io::ErrorKind::NotFound,
+            ErrorKind::Unavailable => std::io::ErrorKind::WouldBlock,
+        }
+    }
+}
+
+
+struct Source;
+impl std::io::Read for Source
+{
+    fn read (&mut self, _ : &mut [u8]) -> std::io::Result<usize>
+    {
+        attempt!(attempted() => "Reading source");
+        Ok(0)
+    }
+}
+
+
+#[test]
+fn io_round_trip ()
+{
+    use std::io::Read;
+
+    let err = Source.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().starts_with("Service Error: Row 3 missing\n"));
+
+    let err : Error = epitaph::from_io_error(err).unwrap();
+    assert_eq!(*err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.trace().len(), 3);
+    assert_eq!(err.notes().last().map(|n| &n.1[..]), Some("Reading source"));
+
+    let plain = std::io::Error::other("plain");
+    let plain = epitaph::from_io_error::<Error>(plain).unwrap_err();
+    assert_eq!(plain.to_string(), "plain");
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "derive")]
+
+#[macro_use]
+extern crate epitaph;
+
+use epitaph::kind::{self, sysexits, Describe, GrpcCode, HttpStatus, RpcKind, Severity};
+use epitaph::{Classify, ErrorCode, ExitStatus};
+
+
+#[derive(ErrorKind, Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    #[kind(code = "E12", desc = "not found", status = 404, exit = 66, client_fault, json_rpc = -32004)]
+    NotFound,
+    #[kind(desc = "timed out", status = 504, transient)]
+    Timeout(u32),
+    #[kind(transient, not_retryable, severity = "critical", grpc = "ResourceExhausted")]
+    Overloaded{ queued : usize },
+    Internal,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn metadata ()
+{
+    assert_eq!(ErrorKind::NotFound.describe(), "not found");
+    assert_eq!(ErrorKind::Internal.describe(), "Internal");
+    assert_eq!(ErrorKind::NotFound.code(), Some("E12"));
+    assert_eq!(ErrorKind::Timeout(5).code(), None);
+    assert_eq!(ErrorKind::NotFound.http_status(), 404);
+    assert_eq!(ErrorKind::Internal.http_status(), 500);
+    assert_eq!(ErrorKind::NotFound.exit_code(), sysexits::NOINPUT);
+    assert_eq!(ErrorKind::Overloaded{queued: 3}.exit_code(), sysexits::SOFTWARE);
+    assert_eq!(ErrorKind::NotFound.grpc_code(), GrpcCode::NotFound);
+    assert_eq!(ErrorKind::Overloaded{queued: 3}.grpc_code(), GrpcCode::ResourceExhausted);
+    assert_eq!(ErrorKind::NotFound.json_rpc_code(), -32004);
+    assert_eq!(ErrorKind::Internal.json_rpc_code(), -32000);
+
+    assert!(ErrorKind::Timeout(5).is_transient() && ErrorKind::Timeout(5).is_retryable());
+    assert!(ErrorKind::Overloaded{queued: 3}.is_transient() && !ErrorKind::Overloaded{queued: 3}.is_retryable());
+    assert!(ErrorKind::NotFound.is_client_fault());
+    assert_eq!(ErrorKind::NotFound.severity(), Severity::Low);
+    assert_eq!(ErrorKind::Timeout(5).severity(), Severity::Medium);
+    assert_eq!(ErrorKind::Overloaded{queued: 3}.severity(), Severity::Critical);
+    assert_eq!(ErrorKind::Internal.severity(), Severity::High);
+
+    let err : Error = new_err!(ErrorKind::NotFound; "No user 4");
+    assert!(err.to_string().starts_with("Test Error [E12]: No user 4\n"));
+    assert!(kind::is_client_fault(&err));
+}
@@ -0,0 +1,99 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use epitaph::{ErrorCode, Record};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Missing,
+    Unstable,
+}
+impl ErrorCode for ErrorKind
+{
+    fn code (&self) -> Option<&'static str>
+    {
+        match *self {
+            ErrorKind::Missing  => Some("E0042"),
+            ErrorKind::Unstable => None,
+        }
+    }
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PlainKind
+{
+    Plain,
+}
+
+error_items!{PlainError<Kind = PlainKind> "Plain Error"}
+
+
+explanations!{
+    pub static EXPLANATIONS;
+    "E0042" => "The requested record does not exist.\nCheck the id and try again.",
+    "E0043" => "Reserved.",
+}
+
+
+#[test]
+fn codes ()
+{
+    let missing : Error = new_err!(ErrorKind::Missing; "No record 7");
+    assert!(missing.to_string().starts_with("Test Error [E0042]: No record 7\n"));
+    assert_eq!(epitaph::DetailedError::code(&missing), Some("E0042"));
+    assert_eq!(EXPLANATIONS.explain(&missing), Some("The requested record does not exist.\nCheck the id and try again."));
+
+    let unstable : Error = new_err!(ErrorKind::Unstable, missing; "Lookup failed");
+    assert!(unstable.to_string().starts_with("Test Error: Lookup failed\n"));
+    assert_eq!(EXPLANATIONS.explain(&unstable), None);
+
+    let record = Record::from_error(&unstable);
+    assert_eq!(record.code, None);
+    assert_eq!(record.kind.as_ref().map(|k| &k[..]), Some("Unstable"));
+    assert_eq!(record.cause.as_ref().unwrap().code.as_ref().map(|c| &c[..]), Some("E0042"));
+    assert_eq!(record.cause.as_ref().unwrap().frames.len(), 1);
+
+    let plain : PlainError = new_err!(PlainKind::Plain; "No code");
+    assert_eq!(epitaph::DetailedError::code(&plain), None);
+    assert!(plain.to_string().starts_with("Plain Error: No code\n"));
+}
+
+
+#[cfg(feature = "json")]
+#[test]
+fn record_json ()
+{
+    let missing : Error = new_err!(ErrorKind::Missing; "No record 7");
+    let record = Record::from_error(&missing);
+    let json = record.to_json();
+    assert!(json.contains("\"code\":\"E0042\""));
+    assert_eq!(Record::from_json(&json).unwrap(), record);
+}
+
+
+#[test]
+fn embedded ()
+{
+    let bytes = fs::read(env::current_exe().unwrap()).unwrap();
+    let entries = epitaph::explain::scan(&bytes);
+    assert!(entries.contains(&("E0042".to_owned(), EXPLANATIONS.get("E0042").unwrap().to_owned())));
+    assert!(entries.contains(&("E0043".to_owned(), "Reserved.".to_owned())));
+
+    let explain = match option_env!("CARGO_BIN_EXE_epitaph-explain") {
+        Some(explain) => explain,
+        None          => return,
+    };
+    let out = Command::new(explain).arg(env::current_exe().unwrap()).arg("E0043").output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "Reserved.\n");
+    let out = Command::new(explain).arg(env::current_exe().unwrap()).arg("E9999").output().unwrap();
+    assert_eq!(out.status.code(), Some(65));
+}
@@ -56,3 +56,104 @@ fn funcs ()
 
 
 
+
+
+#[test]
+fn loops ()
+{
+    let mut errors = Vec::new();
+    let mut values = Vec::new();
+    for i in 0..3 {
+        let val = f_attempt_continue!((|r : Result<i32, Error>| errors.push(r.err().unwrap())) if i == 1 { fail_func() } else { success_func() });
+        values.push(val);
+    }
+    assert_eq!(values, vec!(5, 5));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].trace().len(), 2);
+
+    let mut errors = Vec::new();
+    let found = loop {
+        f_attempt_break!((|r : Result<i32, Error>| errors.push(r); false) fail_func() => "Stopped");
+    };
+    assert!(!found);
+    assert_eq!(errors.len(), 1);
+
+    let mut errors = Vec::new();
+    for i in 0..3 {
+        attempt_continue!((|e : Error| errors.push(e)) if i == 2 { fail_func() } else { success_func() } => "Item {}", i);
+    }
+    assert_eq!(errors.len(), 1);
+
+    let mut errors = Vec::new();
+    loop {
+        attempt_break!((|e : Error| errors.push(e)) fail_func());
+    }
+    assert_eq!(errors.len(), 1);
+}
+
+
+#[test]
+fn borrowed_callbacks ()
+{
+    struct Worker <F>
+        where F : FnMut(Result<i32, Error>)
+    {
+        cb : F,
+    }
+    impl <F> Worker<F>
+        where F : FnMut(Result<i32, Error>)
+    {
+        fn run (&mut self, fail : bool)
+        {
+            let val = f_attempt!((self.cb) if fail { fail_func() } else { success_func() });
+            f_ok!((self.cb) val);
+        }
+    }
+
+    let mut results = Vec::new();
+    {
+        let mut worker = Worker{cb: |r : Result<i32, Error>| results.push(r.is_ok())};
+        worker.run(false);
+        worker.run(true);
+    }
+    assert_eq!(results, vec!(true, false));
+
+    let mut failures = 0;
+    let mut cb = |r : Result<i32, Error>| if r.is_err() { failures += 1 };
+    for i in 0..4 {
+        f_attempt_continue!((cb) if i % 2 == 0 { fail_func() } else { success_func() });
+    }
+    assert_eq!(failures, 2);
+}
+
+
+#[test]
+fn sinks ()
+{
+    use std::sync::mpsc;
+
+    fn sink_impl <S> (s : S)
+        where S : epitaph::ResultSink<i32, Error>
+    {
+        let val = s_attempt!((s) success_func());
+        s_attempt!((s) fail_func() => "Failed after {}", val);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    sink_impl(tx);
+    let err = rx.recv().unwrap().err().unwrap();
+    assert_eq!(err.trace().len(), 2);
+
+    let (tx, rx) = mpsc::channel::<Result<i32, Error>>();
+    for i in 0..3 {
+        let val = s_attempt_continue!((&tx) if i == 0 { fail_func() } else { success_func() });
+        epitaph::ResultSink::send_result(&tx, Ok(val + i));
+    }
+    assert!(rx.try_recv().unwrap().is_err());
+    assert_eq!(rx.try_recv().unwrap().unwrap(), 6);
+    assert_eq!(rx.try_recv().unwrap().unwrap(), 7);
+
+    let mut received = None;
+    sink_impl(|r| received = Some(r));
+    assert!(received.unwrap().is_err());
+}
@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use epitaph::{DetailedError, FutureExt};
+
+
+fn fail_func () -> Result<(), Error>
+{
+    err!(ErrorKind::ErrorOne; "Error One");
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    ErrorOne,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+struct NoopWake;
+impl Wake for NoopWake
+{
+    fn wake (self : Arc<Self>) {}
+}
+
+fn block_on <F> (future : F) -> F::Output
+    where F : Future
+{
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(NoopWake));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(val) = future.as_mut().poll(&mut cx) {
+            return val;
+        }
+    }
+}
+
+
+struct YieldOnce <T> (Option<T>, bool);
+impl <T> Future for YieldOnce<T>
+    where T : Unpin
+{
+    type Output = T;
+
+    fn poll (mut self : Pin<&mut Self>, cx : &mut Context) -> Poll<T>
+    {
+        if self.1 {
+            Poll::Ready(self.0.take().unwrap())
+        } else {
+            self.1 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+
+#[test]
+fn traced ()
+{
+    let line = line!() + 1;
+    let err = block_on(YieldOnce(Some(fail_func()), false).traced()).err().unwrap();
+    assert_eq!(err.trace().len(), 2);
+    assert_eq!(err.trace()[1], (file!(), line));
+
+    let err = block_on(std::future::ready(fail_func()).context("Awaiting")).err().unwrap();
+    assert_eq!(err.notes(), &[(1, "Awaiting".to_owned())][..]);
+
+    assert!(block_on(std::future::ready(Ok::<(), Error>(())).traced()).is_ok());
+}
+
+
+#[test]
+fn scoped ()
+{
+    let task = TaskFail(Box::pin(YieldOnce(Some(()), false))).in_scope("task 7");
+    let err = block_on(task.in_scope("outer task"));
+    assert_eq!(err.scopes(), &["outer task".to_owned(), "task 7".to_owned()][..]);
+    assert!(epitaph::scope::snapshot().is_empty());
+
+    let panicked = std::panic::catch_unwind(|| block_on(std::future::poll_fn(|_| -> Poll<()> { panic!("in poll") }).in_scope("doomed")));
+    assert!(panicked.is_err());
+    assert!(epitaph::scope::snapshot().is_empty());
+}
+
+struct TaskFail <F> (Pin<Box<F>>);
+impl <F> Future for TaskFail<F>
+    where F : Future<Output = ()>
+{
+    type Output = Error;
+
+    fn poll (mut self : Pin<&mut Self>, cx : &mut Context) -> Poll<Error>
+    {
+        match self.0.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(()) => Poll::Ready(new_err!(ErrorKind::ErrorOne; "In task")),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::num::ParseIntError;
+
+use epitaph::{DetailedError, TracedIterator};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Parse,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+impl_from_error!(<ParseIntError, Error> |_| ErrorKind::Parse; "Parse failed");
+
+
+#[test]
+fn collect ()
+{
+    let ok : Result<Vec<i32>, Error> = vec!("1", "2").into_iter().map(|s| s.parse::<i32>()).collect_traced();
+    assert_eq!(ok.unwrap(), vec!(1, 2));
+
+    let err : Result<Vec<i32>, Error> = vec!("1", "x", "y").into_iter().map(|s| s.parse::<i32>()).collect_traced();
+    let err = err.err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::Parse);
+    assert!(format!("{}", err).contains("item 1"));
+
+    let all : Result<Vec<i32>, Vec<Error>> = vec!("x", "2", "y").into_iter().map(|s| s.parse::<i32>()).collect_all_errors();
+    let all = all.err().unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(format!("{}", all[1]).contains("item 2"));
+}
+
+
+#[test]
+fn try_map ()
+{
+    let results : Vec<Result<i32, Error>> = vec!("1", "x").into_iter()
+        .try_map_described(|s| format!("{:?}", s), |s| s.parse::<i32>())
+        .collect();
+    assert_eq!(*results[0].as_ref().unwrap(), 1);
+    assert!(format!("{}", results[1].as_ref().err().unwrap()).contains("item 1 (\"x\")"));
+
+    let traced : Result<Vec<Error>, Error> = vec!(new_err!(ErrorKind::Parse; "Inner")).into_iter()
+        .try_map_traced(|e : Error| Err(e))
+        .collect();
+    let traced = traced.err().unwrap();
+    assert_eq!(traced.trace().len(), 2);
+    assert_eq!(traced.notes(), &[(1, "item 0".to_owned())][..]);
+}
@@ -0,0 +1,153 @@
+#[macro_use]
+extern crate epitaph;
+
+use epitaph::kind::{self, Classify, Level, Severity};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Timeout,
+    BadRequest,
+    Corrupt,
+}
+impl Classify for ErrorKind
+{
+    fn is_transient (&self) -> bool { *self == ErrorKind::Timeout }
+    fn is_client_fault (&self) -> bool { *self == ErrorKind::BadRequest }
+    fn severity (&self) -> Severity
+    {
+        match *self {
+            ErrorKind::Corrupt => Severity::Critical,
+            _                  => Severity::Low,
+        }
+    }
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn classify ()
+{
+    let timeout : Error = new_err!(ErrorKind::Timeout; "Timed out");
+    let wrapped : Error = new_err!(ErrorKind::BadRequest, timeout; "Request failed");
+    let multi : Error = new_err!(ErrorKind::BadRequest, epitaph::MultiErr::new(vec!(Box::new(wrapped))); "Batch failed");
+
+    assert_eq!(kind::chain(&multi).count(), 3);
+    assert!(kind::is_transient(&multi));
+    assert!(kind::is_retryable(&multi));
+    assert!(kind::is_client_fault(&multi));
+    assert_eq!(kind::severity(&multi), Severity::Low);
+    assert_eq!(kind::log_level(&multi), Level::Info);
+
+    let corrupt : Error = new_err!(ErrorKind::Corrupt, multi; "Corrupted");
+    assert_eq!(kind::severity(&corrupt), Severity::Critical);
+    assert_eq!(kind::log_level(&corrupt), Level::Error);
+
+    assert!(std::io::ErrorKind::TimedOut.is_transient());
+    assert_eq!(std::io::ErrorKind::NotFound.severity(), Severity::Low);
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ServiceKind
+{
+    Failed,
+}
+impl Classify for ServiceKind {}
+
+error_items!{ServiceError<Kind = ServiceKind> "Service Error"}
+
+
+#[test]
+fn classify_causes ()
+{
+    // the transient cause is another `error_items!` type than the error being asked about
+    let timeout : Error = new_err!(ErrorKind::Timeout; "Timed out");
+    let service : ServiceError = new_err!(ServiceKind::Failed, timeout; "Lookup failed");
+
+    assert_eq!(kind::chain(&service).count(), 1);
+    assert!(kind::is_transient(&service));
+    assert!(kind::is_retryable(&service));
+    assert!(!kind::is_client_fault(&service));
+    assert_eq!(kind::severity(&service), Severity::High);
+}
+
+
+error_items!{OpenError<Kind = kind::OpenKind> "Plugin Error"}
+
+
+#[test]
+fn open_kinds ()
+{
+    use epitaph::ErrorCode;
+    use epitaph::kind::{KindInfo, OpenKind};
+
+    let missing = OpenKind::register(KindInfo::new("storage", "missing").code("S404").status(404).client_fault());
+    let busy = OpenKind::register(KindInfo::new("storage", "busy").transient());
+    assert!(missing != busy);
+    assert_eq!(OpenKind::register(KindInfo::new("storage", "missing")), missing);
+    assert_eq!(OpenKind::lookup("storage", "busy"), Some(busy));
+    assert_eq!(OpenKind::lookup("storage", "full"), None);
+    assert!(kind::registered().contains(&missing));
+    assert_eq!(missing.to_string(), "storage::missing");
+    assert_eq!(missing.code(), Some("S404"));
+
+    let err : OpenError = new_err!(busy; "Disk busy");
+    let err : OpenError = new_err!(missing, err; "No blob 3");
+    assert!(err.to_string().starts_with("Plugin Error [S404]: No blob 3\n"));
+    assert!(kind::is_transient(&err));
+    assert_eq!(*epitaph::DetailedError::kind(&err), missing);
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum InputKind
+{
+    Missing,
+    Malformed,
+    Other,
+}
+impl kind::InferKind for InputKind
+{
+    fn unknown () -> InputKind { InputKind::Other }
+
+
+    fn from_io_kind (kind : std::io::ErrorKind) -> InputKind
+    {
+        match kind {
+            std::io::ErrorKind::NotFound => InputKind::Missing,
+            _                            => InputKind::Other,
+        }
+    }
+
+
+    fn from_parse_int (_ : &std::num::ParseIntError) -> InputKind { InputKind::Malformed }
+}
+
+error_items!{InputError<Kind = InputKind> "Input Error"}
+
+impl_from_error!{<std::str::Utf8Error, InputError>; "Bad text"}
+
+
+fn read_count (path : &str) -> Result<u32, InputError>
+{
+    let text = attempt_err!(std::fs::read_to_string(path); "Could not read {}", path);
+    Ok(attempt_err!(text.trim().parse::<u32>(); "Bad count"))
+}
+
+
+#[test]
+fn inferred ()
+{
+    use epitaph::DetailedError;
+
+    assert_eq!(*read_count("/nonexistent/count").unwrap_err().kind(), InputKind::Missing);
+    assert_eq!(*read_count("Cargo.toml").unwrap_err().kind(), InputKind::Malformed);
+
+    let bytes = vec!(0xff);
+    let err : InputError = std::str::from_utf8(&bytes).unwrap_err().into();
+    assert_eq!(*err.kind(), InputKind::Other);
+    assert_eq!(err.msg(), "Bad text");
+}
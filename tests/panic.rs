@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::error::Error as StdError;
+
+use epitaph::DetailedError;
+use epitaph::panic::PanicErr;
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    PluginPanic,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+fn plugin (fail : bool) -> i32
+{
+    if fail {
+        panic!("plugin {} failed", "demo");
+    }
+    5
+}
+
+
+#[test]
+fn catch ()
+{
+    epitaph::panic::install_location_hook();
+
+    let ok : Result<i32, Error> = catch!(ErrorKind::PluginPanic; plugin(false));
+    assert_eq!(ok.unwrap(), 5);
+
+    let err : Result<i32, Error> = catch!(ErrorKind::PluginPanic; plugin(true));
+    let err = err.err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::PluginPanic);
+    assert_eq!(err.trace().len(), 1);
+    assert_eq!(err.trace()[0].1, 36);
+
+    let cause = err.source().unwrap().downcast_ref::<PanicErr>().unwrap();
+    assert_eq!(cause.msg(), "plugin demo failed");
+    let (file, line) = cause.location().unwrap();
+    assert!(file.ends_with("panic.rs"));
+    assert_eq!(line, 22);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate epitaph;
+extern crate serde_json;
+
+use epitaph::kind::{Describe, ErrorCode, HttpStatus};
+use epitaph::problem::{Problem, ProblemOptions};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    NotFound,
+}
+impl Describe for ErrorKind
+{
+    fn describe (&self) -> &'static str { "Not Found" }
+}
+impl ErrorCode for ErrorKind
+{
+    fn code (&self) -> Option<&'static str> { Some("E404") }
+}
+impl HttpStatus for ErrorKind
+{
+    fn http_status (&self) -> u16 { 404 }
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn problem ()
+{
+    let err : Error = new_err!(ErrorKind::NotFound, epitaph::StringErr::from_str("no such row"); "No order 12");
+
+    let problem = Problem::from_error(&err, &ProblemOptions::default()).instance("/orders/12").extension("order", 12).extension("status", 200);
+    let json : serde_json::Value = serde_json::from_str(&problem.to_json()).unwrap();
+    assert_eq!(json, serde_json::json!({
+        "type": "about:blank",
+        "title": "Not Found",
+        "status": 404,
+        "detail": "No order 12",
+        "instance": "/orders/12",
+        "code": "E404",
+        "order": 12,
+    }));
+    assert!(!problem.to_json().contains("no such row"));
+
+    let options = ProblemOptions{type_base: Some("https://example.com/errors/".to_owned()), debug: true};
+    let problem = Problem::from_error(&err, &options);
+    assert_eq!(problem.type_uri, "https://example.com/errors/E404");
+    let debug = &problem.extensions["epitaph"];
+    assert_eq!(debug["frames"][0]["file"], "tests/problem.rs");
+    assert_eq!(debug["cause"]["msg"], "no such row");
+}
+
+
+#[test]
+fn problem_fields ()
+{
+    use epitaph::ErrorParts;
+
+    let err : Error = new_err!(ErrorKind::NotFound; "No order 12");
+    let err = err.with_field("order_id", 12).with_field("status", "lost");
+    let problem = Problem::from_error(&err, &ProblemOptions{type_base: None, debug: true});
+    assert_eq!(problem.extensions["order_id"], "12");
+    // a field can't replace the document's own members
+    assert_eq!(problem.status, 404);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&problem.to_json()).unwrap()["status"], 404);
+    assert_eq!(problem.extensions["epitaph"]["fields"][0], serde_json::json!(["order_id", "12"]));
+}
@@ -59,3 +59,31 @@ fn promises ()
 }
 
 
+
+
+impl_from_await_error!(<Error> ErrorKind::ErrorThree; "Promise broken");
+
+#[test]
+fn traced_promises ()
+{
+    fn promises_impl (p : epitaph::promise::TracedPromise<i32, Error>)
+    {
+        let p = p.pass();
+        p_attempt!((p) success_func());
+    }
+
+    let (p, f) = epitaph::promise::traced_promise_future::<i32, Error>();
+    promises_impl(p);
+    let err = f.await().unwrap().err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::ErrorThree);
+    assert_eq!(err.trace().len(), 2);
+    assert!(format!("{}", err).contains(&format!("dropped unfulfilled on {} at {}:", epitaph::thread::current(), file!())));
+
+    fn traced_impl (p : epitaph::promise::TracedPromise<i32, Error>)
+    {
+        p_attempt!((p) fail_func());
+    }
+    let (p, f) = epitaph::promise::traced_promise_future::<i32, Error>();
+    traced_impl(p);
+    assert_eq!(*f.await().unwrap().err().unwrap().kind(), ErrorKind::ErrorOne);
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate epitaph;
+
+use std::error::Error as StdError;
+
+use epitaph::{DetailedError, ErrorParts, Record, RemoteErr};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Worker,
+    Parse,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+fn worker () -> String
+{
+    let parse : Error = new_err!(ErrorKind::Parse, epitaph::StringErr::from_str("bad digit"); "Line 4 is not a number");
+    let failed : Error = new_err!(ErrorKind::Worker, parse; "Job 9 failed");
+    Record::from_error(&failed).to_json()
+}
+
+
+#[test]
+fn remote ()
+{
+    let remote = RemoteErr::from_json(&worker()).unwrap();
+    assert_eq!(remote.kind_name(), Some("Worker"));
+    assert_eq!(remote.msg(), "Job 9 failed");
+    assert_eq!(remote.frames()[0].line, 24);
+
+    let parse = remote.source().unwrap().downcast_ref::<RemoteErr>().unwrap();
+    assert_eq!(parse.kind_name(), Some("Parse"));
+    let leaf = parse.source().unwrap().downcast_ref::<RemoteErr>().unwrap();
+    assert_eq!(leaf.kind_name(), None);
+    assert_eq!(leaf.msg(), "bad digit");
+    assert!(leaf.source().is_none());
+
+    let local : Error = new_err!(ErrorKind::Worker, remote; "Batch failed");
+    let text = local.to_string();
+    let lines : Vec<&str> = text.lines().collect();
+    assert_eq!(&lines[2..], &[
+        "    ~ process boundary",
+        "Test Error: Job 9 failed",
+        "    @ tests/remote.rs:24",
+        "Test Error: Line 4 is not a number",
+        "    @ tests/remote.rs:23",
+        "bad digit",
+    ]);
+    assert_eq!(*local.kind(), ErrorKind::Worker);
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StoreKind
+{
+    Corrupt,
+}
+
+error_items!{StoreError<Kind = StoreKind> "Store Error"}
+
+
+#[test]
+fn remote_mixed_types ()
+{
+    let store : StoreError = new_err!(StoreKind::Corrupt, epitaph::StringErr::from_str("leaf"); "Page 3 is corrupt");
+    let failed : Error = new_err!(ErrorKind::Worker, store.with_field("page", 3); "Job 9 failed");
+    let remote = RemoteErr::from_json(&Record::from_error(&failed).to_json()).unwrap();
+    assert_eq!(remote.kind_name(), Some("Worker"));
+    assert!(remote.fields().is_empty());
+
+    let store = remote.source().unwrap().downcast_ref::<RemoteErr>().unwrap();
+    assert_eq!(store.kind_name(), Some("Corrupt"));
+    assert_eq!(store.msg(), "Page 3 is corrupt");
+    assert_eq!(store.frames()[0].line, 71);
+    assert_eq!(store.fields(), &[("page".to_owned(), "3".to_owned())][..]);
+
+    let leaf = store.source().unwrap().downcast_ref::<RemoteErr>().unwrap();
+    assert_eq!(leaf.msg(), "leaf");
+    assert!(leaf.source().is_none());
+    assert_eq!(remote.to_string().matches("leaf").count(), 1);
+}
@@ -0,0 +1,160 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::error::Error as StdError;
+
+use epitaph::panic::PanicErr;
+use epitaph::report::{self, Format, Options, Verbosity};
+
+
+fn fail_func () -> Result<(), Error>
+{
+    err!(ErrorKind::ErrorOne; "Error One");
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    ErrorOne,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn options ()
+{
+    let err = fail_func().err().unwrap();
+    assert_eq!(format!("{}", err).lines().count(), 2);
+
+    report::set_options(Options{verbosity: Verbosity::Brief, color: false, format: Format::Text});
+    assert_eq!(format!("{}", err), "Test Error: Error One\n");
+
+    report::set_options(Options{verbosity: Verbosity::Normal, color: true, format: Format::Text});
+    assert!(format!("{}", err).starts_with("\x1b[1;31mTest Error: Error One\x1b[0m\n"));
+
+    epitaph::panic::install_hook(Options{verbosity: Verbosity::Brief, color: false, format: Format::Text});
+    let caught : Result<(), Error> = catch!(ErrorKind::ErrorOne; panic!("hooked"));
+    let caught = caught.err().unwrap();
+    let cause = caught.source().unwrap().downcast_ref::<PanicErr>().unwrap();
+    assert!(cause.location().unwrap().0.ends_with("report.rs"));
+    assert_eq!(format!("{}", caught), "Test Error: hooked\n");
+
+    report::set_options(Options::default());
+}
+
+
+impl epitaph::ExitStatus for ErrorKind
+{
+    fn exit_code (&self) -> u8 { epitaph::kind::sysexits::DATAERR }
+}
+
+#[test]
+fn exit_codes ()
+{
+    use epitaph::{ExitStatus, Report};
+
+    let report : Report<Error> = fail_func().into();
+    assert_eq!(report.exit_code(), 65);
+    assert_eq!(Report::<Error>::from(Ok(())).exit_code(), 0);
+
+    fn main_impl () -> Result<(), Report<Error>>
+    {
+        fail_func()?;
+        Ok(())
+    }
+    let report = main_impl().err().unwrap();
+    assert_eq!(report.exit_code(), 65);
+    assert!(format!("{}", report.error().unwrap()).contains("Test Error: Error One"));
+
+    assert_eq!(std::io::ErrorKind::NotFound.exit_code(), 66);
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StoreKind
+{
+    Corrupt,
+}
+
+error_items!{StoreError<Kind = StoreKind> "Store Error"}
+
+
+// a foreign error that renders its cause as part of its own message
+#[derive(Debug)]
+struct Wrapped (StoreError);
+impl std::fmt::Display for Wrapped
+{
+    fn fmt (&self, fmt : &mut std::fmt::Formatter) -> std::fmt::Result { write!(fmt, "wrapped: {}", self.0) }
+}
+impl StdError for Wrapped
+{
+    fn source (&self) -> Option<&(dyn StdError + 'static)> { Some(&self.0) }
+}
+
+
+// a foreign error that doesn't render its cause
+#[derive(Debug)]
+struct Plain (&'static str, Option<Box<dyn StdError + Send + Sync>>);
+impl std::fmt::Display for Plain
+{
+    fn fmt (&self, fmt : &mut std::fmt::Formatter) -> std::fmt::Result { write!(fmt, "{}", self.0) }
+}
+impl StdError for Plain
+{
+    fn source (&self) -> Option<&(dyn StdError + 'static)> { self.1.as_ref().map(|e| &**e as &(dyn StdError + 'static)) }
+}
+
+
+#[test]
+fn records ()
+{
+    use epitaph::Record;
+
+    let store : StoreError = new_err!(StoreKind::Corrupt, epitaph::StringErr::from_str("leaf"); "Page 3 is corrupt");
+    let err : Error = new_err!(ErrorKind::ErrorOne, store; "Lookup failed");
+    let record = Record::from_error(&err);
+    assert_eq!(record.kind.as_ref().unwrap(), "ErrorOne");
+
+    // a cause of another `error_items!` type keeps its kind and frames
+    let store = record.cause.as_ref().unwrap();
+    assert_eq!(store.desc, "Store Error");
+    assert_eq!(store.kind.as_ref().unwrap(), "Corrupt");
+    assert_eq!(store.msg, "Page 3 is corrupt");
+    assert_eq!(store.frames.len(), 1);
+
+    let leaf = store.cause.as_ref().unwrap();
+    assert_eq!(leaf.msg, "leaf");
+    assert!(leaf.cause.is_none());
+
+    let store : StoreError = new_err!(StoreKind::Corrupt, epitaph::StringErr::from_str("leaf"); "Page 3 is corrupt");
+    let err : Error = new_err!(ErrorKind::ErrorOne, Wrapped(store); "Lookup failed");
+    let record = Record::from_error(&err);
+    let wrapped = record.cause.as_ref().unwrap();
+    assert!(wrapped.msg.starts_with("wrapped: Store Error"));
+    assert_eq!(wrapped.cause.as_ref().unwrap().kind.as_ref().unwrap(), "Corrupt");
+
+    // a plain cause whose text happens to be part of the one above doesn't end the chain
+    let store : StoreError = new_err!(StoreKind::Corrupt; "Page 3 is corrupt");
+    let reset = Plain("reset", Some(Box::new(store)));
+    let err : Error = new_err!(ErrorKind::ErrorOne, Plain("connection reset by peer", Some(Box::new(reset))); "Lookup failed");
+    let record = Record::from_error(&err);
+    let conn = record.cause.as_ref().unwrap();
+    assert_eq!(conn.msg, "connection reset by peer");
+    assert_eq!(conn.cause.as_ref().unwrap().kind.as_ref().unwrap(), "Corrupt");
+}
+
+
+#[test]
+fn exit_status ()
+{
+    let explain = match option_env!("CARGO_BIN_EXE_epitaph-explain") {
+        Some(explain) => explain,
+        None          => return,
+    };
+    // `epitaph-explain`'s `main` returns `Result<(), Report<_>>`
+    let out = std::process::Command::new(explain).output().unwrap();
+    assert_eq!(out.status.code(), Some(64));
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with("Error: epitaph-explain: usage: "));
+}
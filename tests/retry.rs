@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use epitaph::Classify;
+use epitaph::retry::{self, Policy};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    Busy,
+    Denied,
+}
+impl Classify for ErrorKind
+{
+    fn is_transient (&self) -> bool { *self == ErrorKind::Busy }
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+fn policy (slept : &Rc<RefCell<Vec<Duration>>>) -> Policy
+{
+    let slept = slept.clone();
+    Policy::new(3).backoff(Duration::from_millis(10), Duration::from_millis(15)).seed(7).sleep(move |d| slept.borrow_mut().push(d))
+}
+
+
+#[test]
+fn retries ()
+{
+    let slept = Rc::new(RefCell::new(Vec::new()));
+    let calls = Cell::new(0);
+    let res : Result<i32, Error> = retry::retry_with(policy(&slept), |kind| *kind == ErrorKind::Busy, || {
+        calls.set(calls.get() + 1);
+        if calls.get() < 3 {
+            Err(new_err!(ErrorKind::Busy; "Busy {}", calls.get()))
+        } else {
+            Ok(5)
+        }
+    });
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(*slept.borrow(), policy(&slept).delays().take(2).collect::<Vec<_>>());
+    assert!(slept.borrow()[0] <= Duration::from_millis(10));
+    assert!(slept.borrow()[1] <= Duration::from_millis(15));
+
+    let slept = Rc::new(RefCell::new(Vec::new()));
+    let calls = Cell::new(0);
+    let err = retry!(policy(&slept); {
+        calls.set(calls.get() + 1);
+        Err::<(), Error>(new_err!(ErrorKind::Busy; "Busy {}", calls.get()))
+    }).unwrap_err();
+    assert_eq!(calls.get(), 3);
+    assert_eq!(slept.borrow().len(), 2);
+    let text = err.to_string();
+    assert!(text.starts_with("Test Error: gave up after 3 attempts"));
+    assert!(text.contains("3 errors:"));
+    assert!(text.contains("Busy 1") && text.contains("Busy 2") && text.contains("Busy 3"));
+
+    let calls = Cell::new(0);
+    let err = retry!(policy(&slept); ErrorKind::Busy => {
+        calls.set(calls.get() + 1);
+        Err::<(), Error>(new_err!(ErrorKind::Denied; "Denied"))
+    }).unwrap_err();
+    assert_eq!(calls.get(), 1);
+    assert_eq!(err.to_string().lines().next(), Some("Test Error: Denied"));
+}
+
+
+#[test]
+fn nan_jitter ()
+{
+    let delays : Vec<Duration> = Policy::new(3).backoff(Duration::from_millis(10), Duration::from_millis(40)).jitter(f64::NAN).delays().take(2).collect();
+    assert_eq!(delays, vec!(Duration::from_millis(10), Duration::from_millis(20)));
+}
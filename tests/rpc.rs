@@ -0,0 +1,110 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+extern crate epitaph;
+extern crate serde_json;
+
+use epitaph::DetailedError;
+use epitaph::kind::{GrpcCode, HttpStatus, RpcKind};
+use epitaph::rpc::{JsonRpcError, Status};
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    NotFound,
+    Conflict,
+}
+impl HttpStatus for ErrorKind
+{
+    fn http_status (&self) -> u16
+    {
+        match *self {
+            ErrorKind::NotFound => 404,
+            ErrorKind::Conflict => 409,
+        }
+    }
+}
+impl RpcKind for ErrorKind
+{
+    fn json_rpc_code (&self) -> i64
+    {
+        match *self {
+            ErrorKind::NotFound => -32004,
+            ErrorKind::Conflict => -32009,
+        }
+    }
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn grpc ()
+{
+    let err : Error = new_err!(ErrorKind::NotFound; "No order 12");
+    let status = Status::from_error(&err);
+    assert_eq!(status.code, GrpcCode::NotFound);
+    assert_eq!(status.message, "No order 12");
+    assert_eq!(status.record().unwrap().frames[0].file, "tests/rpc.rs");
+
+    let remote = status.into_error();
+    assert_eq!(remote.kind().grpc, GrpcCode::NotFound);
+    assert_eq!(remote.kind().name.as_ref().map(|n| &n[..]), Some("NotFound"));
+    assert_eq!(remote.msg(), "No order 12");
+    assert_eq!(remote.trace()[0].0, "tests/rpc.rs");
+    assert_eq!(Status::from_error(&remote).code, GrpcCode::NotFound);
+    assert!(remote.to_string().contains("    ~ process boundary\nTest Error: No order 12\n"));
+}
+
+
+#[test]
+fn json_rpc ()
+{
+    let err : Error = new_err!(ErrorKind::Conflict; "Order 12 changed");
+    let json = serde_json::to_string(&JsonRpcError::from_error(&err)).unwrap();
+    let object : JsonRpcError = serde_json::from_str(&json).unwrap();
+    assert_eq!(object.code, -32009);
+    assert_eq!(object.message, "Order 12 changed");
+    assert_eq!(object.data.as_ref().unwrap()["kind"], "Conflict");
+
+    let remote = object.into_error();
+    assert_eq!(remote.kind().json_rpc, -32009);
+    assert_eq!(JsonRpcError::from_error(&remote).code, -32009);
+
+    let bare : JsonRpcError = serde_json::from_str(r#"{"code": -32601, "message": "Method not found"}"#).unwrap();
+    let remote = bare.into_error();
+    assert_eq!(remote.kind().name, None);
+    assert!(remote.to_string().starts_with("Remote Error: Method not found\n"));
+}
+
+
+#[test]
+fn rpc_fields ()
+{
+    use epitaph::ErrorParts;
+
+    let err : Error = new_err!(ErrorKind::NotFound; "No order 12");
+    let err = err.with_field("order_id", 12);
+    let status = Status::from_error(&err);
+    assert_eq!(status.record().unwrap().fields, vec!(("order_id".to_owned(), "12".to_owned())));
+    assert_eq!(JsonRpcError::from_error(&err).data.unwrap()["fields"][0][1], "12");
+
+    let remote = status.into_error();
+    assert_eq!(remote.fields(), &[("order_id".to_owned(), "12".to_owned())][..]);
+    assert_eq!(remote.kind().http_status(), 404);
+    assert_eq!(Status::from_error(&remote).record().unwrap().fields.len(), 1);
+}
+
+
+#[test]
+fn http_mapping ()
+{
+    assert_eq!(GrpcCode::from_http_status(418), GrpcCode::FailedPrecondition);
+    assert_eq!(GrpcCode::from_http_status(429), GrpcCode::ResourceExhausted);
+    assert_eq!(GrpcCode::from_http_status(502), GrpcCode::Internal);
+    assert_eq!(GrpcCode::Unavailable.http_status(), 503);
+    for &status in &[400, 401, 403, 404, 409, 429, 499, 501, 503, 504] {
+        assert_eq!(GrpcCode::from_http_status(status).http_status(), status);
+    }
+}
@@ -68,3 +68,116 @@ fn attempts ()
 }
 
 
+
+
+#[test]
+fn boxed ()
+{
+    fn boxed_impl () -> Result<(), Box<dyn StdError + Send + Sync>>
+    {
+        attempt!(fail_func());
+
+        Ok(())
+    }
+
+    let err = boxed_impl().err().unwrap();
+    let err = err.downcast_ref::<Error>().unwrap();
+
+    assert_eq!(*err.kind(), ErrorKind::ErrorOne);
+    assert_eq!(err.trace().len(), 2);
+    assert_eq!(err.trace()[0].1, 17);
+}
+
+
+error_items!{ForeignError<Kind = ErrorKind, Foreign = ErrorKind::ErrorThree> "Foreign Test Error"}
+
+#[test]
+fn foreign ()
+{
+    fn boxed_fail () -> Result<(), Box<dyn StdError + Send + Sync>>
+    {
+        Err(Box::new(epitaph::StringErr::from_str("inner error")))
+    }
+    fn foreign_impl () -> Result<(), ForeignError>
+    {
+        attempt!(boxed_fail());
+
+        Ok(())
+    }
+
+    let err = foreign_impl().err().unwrap();
+
+    assert_eq!(*err.kind(), ErrorKind::ErrorThree);
+    assert_eq!(err.trace().len(), 1);
+    assert!(err.source().unwrap().downcast_ref::<epitaph::StringErr>().is_some());
+}
+
+
+#[test]
+fn recovery ()
+{
+    fn or_impl () -> i32
+    {
+        attempt_or!(fail_func().map(|_| 1) => 2)
+    }
+    fn unless_impl (fallback : bool) -> Result<i32, Error>
+    {
+        let val = if fallback {
+            attempt_unless!(fail_func().map(|_| 1); ErrorKind::ErrorTwo | ErrorKind::ErrorOne => 2)
+        } else {
+            attempt_unless!(fail_func().map(|_| 1); ErrorKind::ErrorTwo => 2, ErrorKind::ErrorThree => 3)
+        };
+
+        Ok(val)
+    }
+    fn any_impl (last : Result<i32, Error>) -> Result<i32, Error>
+    {
+        let val = attempt_any!(fail_func().map(|_| 1), fail_func().map(|_| 2), last => ErrorKind::ErrorTwo; "All failed");
+
+        Ok(val)
+    }
+
+    assert_eq!(or_impl(), 2);
+    assert_eq!(unless_impl(true).unwrap(), 2);
+    assert_eq!(unless_impl(false).err().unwrap().trace().len(), 2);
+    assert_eq!(any_impl(Ok(3)).unwrap(), 3);
+
+    let err = any_impl(fail_func().map(|_| 3)).err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::ErrorTwo);
+    assert_eq!(err.source().unwrap().downcast_ref::<epitaph::MultiErr>().unwrap().errors().len(), 3);
+
+    // `continue` and `break` in an alternative belong to the caller's loop
+    fn skipping_impl () -> Result<Vec<i32>, Error>
+    {
+        let mut found = Vec::new();
+        for i in 0..4 {
+            let val = attempt_any!(if i == 1 { continue } else { fail_func().map(|_| 1) }, if i == 3 { break } else { success_func().map(|_| i) } => ErrorKind::ErrorTwo; "All failed");
+            found.push(val);
+        }
+        Ok(found)
+    }
+    assert_eq!(skipping_impl().unwrap(), vec!(0, 2));
+}
+
+
+#[test]
+fn scopes ()
+{
+    let err = {
+        let _outer = scope!("handling request {}", 42);
+        let _inner = scope!("migrating table users");
+        fail_func().err().unwrap()
+    };
+    assert_eq!(err.scopes(), &["handling request 42".to_owned(), "migrating table users".to_owned()][..]);
+    assert!(format!("{}", err).contains("    in migrating table users\n    in handling request 42\n"));
+
+    assert!(fail_func().err().unwrap().scopes().is_empty());
+
+    // a guard dropped out of order only takes its own scope with it
+    let outer = scope!("outer");
+    let inner = scope!("inner");
+    drop(outer);
+    assert_eq!(fail_func().err().unwrap().scopes(), &["inner".to_owned()][..]);
+    drop(inner);
+    assert!(fail_func().err().unwrap().scopes().is_empty());
+}
@@ -0,0 +1,155 @@
+#[macro_use]
+extern crate epitaph;
+
+use std::error::Error as StdError;
+use std::thread;
+
+use epitaph::DetailedError;
+
+
+fn fail_func () -> Result<(), Error>
+{
+    err!(ErrorKind::ErrorOne; "Error One");
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorKind
+{
+    ErrorOne,
+}
+
+error_items!{Error<Kind = ErrorKind> "Test Error"}
+
+
+#[test]
+fn channel_hops ()
+{
+    let (tx, rx) = epitaph::thread::traced_channel::<(), Error, _>("jobs");
+    thread::Builder::new().name("worker".to_owned()).spawn(move || {
+        tx.send(fail_func()).unwrap();
+    }).unwrap().join().unwrap();
+
+    let err = rx.recv().unwrap().err().unwrap();
+    assert_eq!(err.hops().len(), 1);
+
+    let hop = &err.hops()[0];
+    assert_eq!(hop.from, "thread 'worker'");
+    assert_eq!(hop.to.as_ref().unwrap(), &epitaph::thread::current());
+    assert_eq!(hop.via, "channel 'jobs'");
+    assert_eq!(hop.at, 1);
+    assert!(format!("{}", err).contains("    ~ thread 'worker' -> "));
+}
+
+
+#[test]
+fn sink_hops ()
+{
+    fn worker <F> (done : F)
+        where F : FnOnce(Result<(), Error>)
+    {
+        f_attempt!((done) fail_func());
+    }
+
+    let (tx, rx) = epitaph::thread::traced_channel::<(), Error, _>("results");
+    thread::Builder::new().name("callback".to_owned()).spawn(move || {
+        worker(|r| { let _ = tx.send(r); });
+    }).unwrap().join().unwrap();
+
+    // the callback is a hop of its own, which the channel it forwards to completes
+    let err = rx.recv().unwrap().err().unwrap();
+    assert_eq!(err.trace().len(), 2);
+    assert_eq!(err.hops().len(), 2);
+    assert_eq!(err.hops()[0].from, "thread 'callback'");
+    assert_eq!(err.hops()[0].via, "done");
+    assert_eq!(err.hops()[0].at, 2);
+    assert!(err.hops()[0].to.is_none());
+    assert_eq!(err.hops()[1].via, "channel 'results'");
+    assert_eq!(err.hops()[1].at, 2);
+    assert!(err.hops()[1].to.is_some());
+    assert!(format!("{}", err).contains("    ~ thread 'callback' via done @ "));
+
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(), Error>>();
+    thread::Builder::new().name("sender".to_owned()).spawn(move || {
+        s_attempt!((tx) fail_func());
+    }).unwrap().join().unwrap();
+    let err = rx.recv().unwrap().err().unwrap();
+    assert_eq!(err.hops().len(), 1);
+    assert_eq!(err.hops()[0].from, "thread 'sender'");
+    assert_eq!(err.hops()[0].via, "tx");
+    assert!(!format!("{}", err).contains("-> ?"));
+
+    // a cause built while sending doesn't take the hop that belongs to the error sent
+    fn nested (tx : std::sync::mpsc::Sender<Result<(), Error>>)
+    {
+        s_err!((tx) ErrorKind::ErrorOne, { let inner : Error = new_err!(ErrorKind::ErrorOne; "inner"); inner }; "outer");
+    }
+    let (tx, rx) = std::sync::mpsc::channel::<Result<(), Error>>();
+    nested(tx);
+    let err = rx.recv().unwrap().err().unwrap();
+    assert_eq!(err.hops().len(), 1);
+    assert_eq!(err.source().unwrap().downcast_ref::<Error>().unwrap().hops().len(), 0);
+}
+
+
+#[test]
+fn sinks_without_hops ()
+{
+    // an error type of its own that knows nothing about hops
+    #[derive(Debug)]
+    struct Plain (String);
+    impl epitaph::DetailedFromError<Error> for Plain
+    {
+        fn from_error (error : Error, _ : Option<String>, _ : &'static str, _ : u32) -> Plain
+        {
+            Plain(error.msg().to_owned())
+        }
+    }
+
+    fn worker <F> (done : F)
+        where F : FnOnce(Result<(), Plain>)
+    {
+        f_attempt!((done) fail_func());
+    }
+    fn sender (tx : std::sync::mpsc::Sender<Result<(), Plain>>)
+    {
+        s_attempt!((tx) fail_func());
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    sender(tx);
+    assert_eq!(rx.recv().unwrap().unwrap_err().0, "Error One");
+
+    let mut received = None;
+    worker(|r| received = Some(r));
+    assert_eq!(received.unwrap().unwrap_err().0, "Error One");
+}
+
+
+#[test]
+fn spawn_join ()
+{
+    let handle = epitaph::thread::spawn(|| -> Result<i32, Error> { Ok(5) });
+    assert_eq!(handle.join(ErrorKind::ErrorOne).unwrap(), 5);
+
+    let handle = epitaph::thread::spawn_named("failing".to_owned(), || -> Result<(), Error> {
+        fail_func()?;
+        Ok(())
+    }).unwrap();
+    let err = handle.join(ErrorKind::ErrorOne).err().unwrap();
+    assert_eq!(err.trace().len(), 2);
+    assert_eq!(err.hops()[0].from, "thread 'failing'");
+    assert_eq!(err.hops()[0].via, "join");
+
+    let handle = epitaph::thread::spawn_named("panicking".to_owned(), || -> Result<(), Error> {
+        panic!("exploded with {}", 7);
+    }).unwrap();
+    let err = handle.join(ErrorKind::ErrorOne).err().unwrap();
+    assert_eq!(*err.kind(), ErrorKind::ErrorOne);
+    assert_eq!(err.trace().len(), 2);
+    assert!(err.trace()[0].0.ends_with("thread.rs"));
+
+    let cause = err.source().unwrap().downcast_ref::<epitaph::panic::PanicErr>().unwrap();
+    assert_eq!(cause.msg(), "exploded with 7");
+    assert_eq!(cause.thread(), Some("thread 'panicking'"));
+}